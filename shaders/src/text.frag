@@ -0,0 +1,16 @@
+#version 450
+
+#extension GL_ARB_separate_shader_objects: enable
+
+layout (set = 0, binding = 0) uniform texture2D fontAtlas;
+layout (set = 0, binding = 1) uniform sampler fontSampler;
+
+layout (location = 0) in vec2 fragTexCoord;
+layout (location = 1) in vec4 fragColor;
+
+layout (location = 0) out vec4 outColor;
+
+void main() {
+
+    outColor = vec4(fragColor.rgb, fragColor.a * texture(sampler2D(fontAtlas, fontSampler), fragTexCoord).r);
+}
@@ -0,0 +1,326 @@
+use std::collections::VecDeque;
+
+use cgmath::Vector2;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::scene::{Action, Direction, Scene};
+use crate::score::Side;
+
+/// How good the computer is at the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    fn profile(self) -> Profile {
+        match self {
+            Difficulty::Easy => Profile {
+                reaction_delay: 0.35,
+                prediction_error: 0.9,
+                max_speed: 0.6,
+            },
+            Difficulty::Normal => Profile {
+                reaction_delay: 0.2,
+                prediction_error: 0.4,
+                max_speed: 0.85,
+            },
+            Difficulty::Hard => Profile {
+                reaction_delay: 0.08,
+                prediction_error: 0.05,
+                max_speed: 1.0,
+            },
+        }
+    }
+}
+
+/// The handicaps that make up a difficulty level.
+struct Profile {
+    /// Seconds between something happening to the ball and the computer noticing it
+    reaction_delay: f32,
+    /// Largest distance between where the ball will arrive and where the computer thinks it will
+    prediction_error: f32,
+    /// Average paddle speed the computer allows itself, as a fraction of the paddle speed
+    max_speed: f32,
+}
+
+/// Ball as it was at some point in time.
+#[derive(Clone, Copy, Debug)]
+struct Sighting {
+    time: f32,
+    position: Vector2<f32>,
+    velocity: Vector2<f32>,
+}
+
+/// A computer controlled player.
+///
+/// It watches the scene and drives its paddle with the same actions a human
+/// player triggers with the keyboard, so it plays by the same rules.
+pub struct CpuPlayer {
+    side: Side,
+    profile: Profile,
+    elapsed: f32,
+    /// Recent sightings of the ball, oldest first, to see it with a delay
+    sightings: VecDeque<Sighting>,
+    /// Error added to the predictions for the current approach of the ball
+    error: f32,
+    is_ball_approaching: bool,
+    /// Seconds of movement left before the computer goes over its speed limit
+    speed_allowance: f32,
+    rng: ChaCha8Rng,
+}
+
+impl CpuPlayer {
+    /// Computer player whose mistakes follow from `seed`.
+    pub fn new(side: Side, difficulty: Difficulty, seed: u64) -> Self {
+        Self {
+            side,
+            profile: difficulty.profile(),
+            elapsed: 0.0,
+            sightings: VecDeque::new(),
+            error: 0.0,
+            is_ball_approaching: false,
+            speed_allowance: 0.0,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// Looks at the scene and returns the action for its paddle, unless the paddle already does the right thing.
+    pub fn update(&mut self, scene: &Scene, delta_time: f32) -> Option<Action> {
+        self.elapsed += delta_time;
+        self.sightings.push_back(Sighting {
+            time: self.elapsed,
+            position: scene.ball.position.truncate(),
+            velocity: scene.ball.velocity,
+        });
+        let ball = self.perceived_ball();
+
+        let paddle = match self.side {
+            Side::Left => &scene.left_paddle,
+            Side::Right => &scene.right_paddle,
+        };
+        // Direction the ball travels along X when it comes towards this paddle
+        let towards = match self.side {
+            Side::Left => -1.0,
+            Side::Right => 1.0,
+        };
+
+        let is_ball_approaching = ball.velocity.x * towards > 0.0;
+        if is_ball_approaching && !self.is_ball_approaching {
+            let error = self.profile.prediction_error;
+            self.error = self.rng.gen_range(-error..=error);
+        }
+        self.is_ball_approaching = is_ball_approaching;
+
+        let radius = scene.ball.side_length / 2.0;
+        let target = if is_ball_approaching {
+            let contact_x = paddle.position.x - towards * (paddle.width / 2.0 + radius);
+            let upper = scene.top_wall.position.y + scene.top_wall.height / 2.0 + radius;
+            let lower = scene.bottom_wall.position.y - scene.bottom_wall.height / 2.0 - radius;
+            predict_y(ball.position, ball.velocity, contact_x, upper, lower)
+                .map(|y| y + self.error)
+                .unwrap_or(0.0)
+        } else {
+            // Wait in the middle, where any return is easiest to reach
+            0.0
+        };
+
+        // Stop once a frame of movement would get past the target
+        let distance = target - paddle.position.y;
+        let wanted = if distance.abs() <= paddle.top_speed(scene.paddle_speed) * delta_time {
+            Direction::Stop
+        } else if distance < 0.0 {
+            Direction::Up
+        } else {
+            Direction::Down
+        };
+
+        // Moving only part of the time keeps the average speed within the limit
+        let direction = if wanted != Direction::Stop && self.speed_allowance >= 0.0 {
+            self.speed_allowance += (self.profile.max_speed - 1.0) * delta_time;
+            wanted
+        } else {
+            self.speed_allowance =
+                (self.speed_allowance + self.profile.max_speed * delta_time).min(delta_time);
+            Direction::Stop
+        };
+
+        // The paddle may have been stopped from elsewhere, like when a match is reset
+        let current = if paddle.target_velocity < 0.0 {
+            Direction::Up
+        } else if paddle.target_velocity > 0.0 {
+            Direction::Down
+        } else {
+            Direction::Stop
+        };
+        if direction == current {
+            return None;
+        }
+        Some(Action::paddle(self.side, direction))
+    }
+
+    /// Latest sighting that is at least as old as the reaction delay.
+    fn perceived_ball(&mut self) -> Sighting {
+        let seen_before = self.elapsed - self.profile.reaction_delay;
+        while self.sightings.len() > 1 && self.sightings[1].time <= seen_before {
+            self.sightings.pop_front();
+        }
+        self.sightings[0]
+    }
+}
+
+/// Height at which a ball reaches `target_x`, bouncing off the walls that
+/// keep its centre between `upper` and `lower`, if it's heading there at all.
+fn predict_y(
+    position: Vector2<f32>,
+    velocity: Vector2<f32>,
+    target_x: f32,
+    upper: f32,
+    lower: f32,
+) -> Option<f32> {
+    if velocity.x == 0.0 {
+        return None;
+    }
+    let time = (target_x - position.x) / velocity.x;
+    if time < 0.0 {
+        return None;
+    }
+
+    // Unfold the bounces: the ball moves freely along a line, and every other
+    // court height it travels is mirrored back into the court
+    let height = lower - upper;
+    let travelled = (position.y + velocity.y * time - upper).rem_euclid(2.0 * height);
+    let folded = if travelled > height {
+        2.0 * height - travelled
+    } else {
+        travelled
+    };
+
+    Some(upper + folded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Vector3;
+
+    const TOLERANCE: f32 = 1e-4;
+
+    fn approx_eq(a: Option<f32>, b: f32) -> bool {
+        a.is_some_and(|a| (a - b).abs() < TOLERANCE)
+    }
+
+    #[test]
+    fn prediction_follows_a_straight_line() {
+        let y = predict_y(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(2.0, 1.0),
+            3.0,
+            -3.0,
+            3.0,
+        );
+        assert!(approx_eq(y, 1.5));
+    }
+
+    #[test]
+    fn prediction_bounces_off_walls() {
+        // Hits the lower wall at x = 2, then comes back up one unit
+        let y = predict_y(
+            Vector2::new(0.0, 2.0),
+            Vector2::new(1.0, 0.5),
+            4.0,
+            -3.0,
+            3.0,
+        );
+        assert!(approx_eq(y, 2.0));
+
+        // Crosses the whole court once and bounces off both walls
+        let y = predict_y(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 4.0),
+            3.0,
+            -3.0,
+            3.0,
+        );
+        assert!(approx_eq(y, 0.0));
+    }
+
+    #[test]
+    fn prediction_ignores_a_ball_going_away() {
+        let y = predict_y(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(-1.0, 0.0),
+            3.0,
+            -3.0,
+            3.0,
+        );
+        assert_eq!(y, None);
+    }
+
+    #[test]
+    fn cpu_heads_for_the_ball() {
+        let mut scene = Scene::new(0);
+        scene.ball.position = Vector3::new(0.0, 0.0, 0.0);
+        scene.ball.velocity = Vector2::new(4.0, -2.0);
+        let mut cpu = CpuPlayer::new(Side::Right, Difficulty::Hard, 0);
+
+        let action = cpu.update(&scene, 0.01).unwrap();
+        assert!(matches!(action, Action::RightPaddleUp));
+        // The paddle already goes the right way, so there is nothing new to do
+        scene.handle_action(action);
+        assert!(cpu.update(&scene, 0.01).is_none());
+    }
+
+    #[test]
+    fn cpu_reacts_late() {
+        let mut scene = Scene::new(0);
+        scene.ball.velocity = Vector2::new(-4.0, 0.0);
+        let mut cpu = CpuPlayer::new(Side::Right, Difficulty::Easy, 0);
+        cpu.update(&scene, 0.1);
+
+        // The ball turns around far from the centre, but the computer still sees it leaving
+        scene.ball.position = Vector3::new(-3.0, -2.0, 0.0);
+        scene.ball.velocity = Vector2::new(4.0, -1.0);
+        assert!(cpu.update(&scene, 0.1).is_none());
+        assert!(matches!(
+            cpu.update(&scene, 0.4),
+            Some(Action::RightPaddleUp)
+        ));
+    }
+
+    #[test]
+    fn hard_cpu_returns_the_ball() {
+        let mut scene = Scene::new(0);
+        scene.ball.velocity = Vector2::new(4.0, 2.5);
+        let mut cpu = CpuPlayer::new(Side::Right, Difficulty::Hard, 0);
+
+        let delta_time = 1.0 / 60.0;
+        for _ in 0..240 {
+            if let Some(action) = cpu.update(&scene, delta_time) {
+                scene.handle_action(action);
+            }
+            scene.update(delta_time);
+            if scene.ball.velocity.x < 0.0 {
+                break;
+            }
+        }
+
+        assert_eq!(scene.score().left, 0);
+        assert!(scene.ball.velocity.x < 0.0);
+    }
+}
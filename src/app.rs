@@ -0,0 +1,383 @@
+use crate::config::Config;
+use crate::game::{Game, GamePhase};
+use crate::graphics_manager::fps_limiter::FPSLimiter;
+use crate::graphics_manager::{self, GraphicsManager};
+pub use crate::graphics_manager::error::GraphicsError;
+use crate::hud;
+use crate::input::gamepad::{self, Gamepads};
+use crate::input::mouse::MousePaddle;
+use crate::input::{HeldKeys, InputAction, Key, KeyBindings};
+use crate::menu::{Menu, MenuCommand, MenuInput, MenuScreen};
+use crate::scene;
+use crate::score::Side;
+use crate::timestep::FixedTimestep;
+
+use winit::event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoop};
+
+enum Action {
+    Quit
+}
+
+enum PongRustActions {
+    SceneAction(scene::Action),
+    SystemAction(Action),
+}
+
+struct PongRust {
+    graphics_manager: GraphicsManager,
+    game: Game,
+    menu: Menu,
+    bindings: KeyBindings,
+    left_keys: HeldKeys,
+    right_keys: HeldKeys,
+    gamepads: Gamepads,
+    mouse: Option<MousePaddle>,
+    /// Last position of the mouse cursor over the window, in pixels from its top left corner
+    cursor: Option<[f32; 2]>,
+    tick_counter: FPSLimiter,
+    timestep: FixedTimestep,
+    show_fps: bool,
+    /// Set when the renderer failed, so the game exits with an error
+    has_failed: bool,
+}
+
+impl PongRust {
+    pub fn main_loop(mut self, event_loop: EventLoop<()>) {
+        event_loop.run(move |event, _, control_flow| match event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::CloseRequested => self.quit(control_flow),
+                WindowEvent::KeyboardInput { input, .. } => {
+                    if let Some(action) = self.handle_keyboard_input(input) {
+                        match action {
+                            PongRustActions::SystemAction(Action::Quit) => self.quit(control_flow),
+                            PongRustActions::SceneAction(action) => {
+                                self.game.handle_scene_action(action);
+                            }
+                        }
+                    }
+                }
+                WindowEvent::Resized(_) => self.graphics_manager.window_resized(),
+                WindowEvent::CursorMoved { position, .. } => {
+                    self.cursor = Some([position.x as f32, position.y as f32]);
+                }
+                // Nobody can play a window they are not looking at
+                WindowEvent::Focused(false)
+                    if self.game.phase == GamePhase::Playing && !self.game.is_replay() =>
+                {
+                    self.pause();
+                }
+                _ => {}
+            },
+            Event::MainEventsCleared => {
+                self.graphics_manager.window_request_redraw();
+            }
+            Event::RedrawRequested(_window_id) => {
+                let frame_time = self.tick_counter.delta_time();
+                self.fit_camera_to_window();
+                self.steer_with_gamepads();
+                self.game.play_replay_events();
+                if self.game.phase == GamePhase::Playing {
+                    for _ in 0..self.timestep.advance(frame_time) {
+                        self.steer_with_mouse(self.timestep.step());
+                        self.game.simulate(self.timestep.step());
+                        self.game.play_replay_events();
+                        if self.game.phase != GamePhase::Playing {
+                            break;
+                        }
+                    }
+                }
+                let transforms = self.game.scene.get_model_transforms(self.timestep.alpha());
+
+                let screen_size = self.graphics_manager.screen_size();
+                for text in hud::overlay(self.game.phase, &self.game.scene, &self.menu, &self.game.settings, &self.bindings, screen_size) {
+                    self.graphics_manager.draw_text(
+                        &text.content,
+                        text.position,
+                        text.scale,
+                        text.color,
+                    );
+                }
+
+                if self.show_fps {
+                    let stats = self.tick_counter.stats();
+                    let fps = format!(
+                        "FPS: {:.0}\nmin {:.1} avg {:.1} max {:.1} p99 {:.1} ms",
+                        self.tick_counter.fps(),
+                        stats.min,
+                        stats.avg,
+                        stats.max,
+                        stats.p99,
+                    );
+                    let [width, _] = self.graphics_manager.screen_size();
+                    let [text_width, _] = self.graphics_manager.text_size(&fps, 1.0);
+                    self.graphics_manager.draw_text(
+                        &fps,
+                        [width - text_width - 8.0, 8.0],
+                        1.0,
+                        [0.6, 0.6, 0.6, 1.0],
+                    );
+                }
+
+                if let Err(error) = self.graphics_manager.draw_frame(transforms, &self.game.scene.camera) {
+                    self.fail(error, control_flow);
+                }
+
+                self.tick_counter.tick_frame();
+            }
+            Event::LoopDestroyed => {
+                self.game.stop_recording();
+                if self.has_failed {
+                    std::process::exit(1);
+                }
+            }
+            _ => (),
+        })
+    }
+
+    fn quit(&mut self, control_flow: &mut ControlFlow) {
+        // Nothing can be freed while the graphics card still uses it
+        match self.graphics_manager.device_wait_idle() {
+            Ok(()) => *control_flow = ControlFlow::Exit,
+            Err(error) => self.fail(error, control_flow),
+        }
+    }
+
+    fn fail(&mut self, error: GraphicsError, control_flow: &mut ControlFlow) {
+        log::error!("Could not draw the game: {}", error);
+        self.has_failed = true;
+        *control_flow = ControlFlow::Exit;
+    }
+
+    pub fn handle_keyboard_input(&mut self, input: KeyboardInput) -> Option<PongRustActions> {
+        let KeyboardInput {
+            virtual_keycode,
+            state,
+            ..
+        } = input;
+        if self.game.is_replay() {
+            let key = virtual_keycode.and_then(Key::from_keycode)?;
+            return match (self.bindings.action_of(key), state) {
+                (Some(InputAction::Back), ElementState::Pressed) => {
+                    Some(PongRustActions::SystemAction(Action::Quit))
+                }
+                _ => None,
+            };
+        }
+        if self.game.phase == GamePhase::Menu || self.game.phase == GamePhase::Paused {
+            return match (virtual_keycode, state) {
+                (Some(VirtualKeyCode::Escape), ElementState::Pressed)
+                    if self.menu.rebinding().is_some() =>
+                {
+                    self.menu.cancel_rebinding();
+                    None
+                }
+                (Some(keycode), ElementState::Pressed) if self.menu.rebinding().is_some() => {
+                    if let Some(key) = Key::from_keycode(keycode) {
+                        self.menu.bind(key, &mut self.bindings);
+                    }
+                    None
+                }
+                (Some(keycode), ElementState::Pressed) => self.handle_menu_input(keycode),
+                _ => None,
+            };
+        }
+
+        let key = virtual_keycode.and_then(Key::from_keycode)?;
+        let action = self.bindings.action_of(key)?;
+        if let Some((side, direction)) = action.paddle() {
+            if side == Side::Right && self.game.is_cpu_playing(Side::Right) {
+                return None;
+            }
+            let held = match side {
+                Side::Left => &mut self.left_keys,
+                Side::Right => &mut self.right_keys,
+            };
+            let before = held.direction();
+            match state {
+                ElementState::Pressed => held.press(key, direction),
+                ElementState::Released => held.release(key),
+            }
+            // Repeated presses of a held key don't need to reach the scene
+            let after = held.direction();
+            return Some(after)
+                .filter(|after| *after != before)
+                .map(|after| PongRustActions::SceneAction(scene::Action::paddle(side, after)));
+        }
+
+        match (action, state) {
+            (InputAction::Back, ElementState::Pressed) => {
+                match self.game.phase {
+                    GamePhase::Playing => {
+                        self.pause();
+                        None
+                    },
+                    _ => {
+                        self.menu.open(MenuScreen::Main);
+                        self.game.set_phase(GamePhase::Menu);
+                        Some(PongRustActions::SceneAction(scene::Action::ResetGame))
+                    }
+                }
+            },
+            (InputAction::Serve, ElementState::Pressed) => {
+                match self.game.phase {
+                    GamePhase::Start => {
+                        self.game.set_phase(GamePhase::Playing);
+                        Some(PongRustActions::SceneAction(scene::Action::Kickoff))
+
+                    },
+                    GamePhase::Playing => {
+                        self.pause();
+                        None
+                    },
+                    GamePhase::End => {
+                        self.game.set_phase(GamePhase::Start);
+                        Some(PongRustActions::SceneAction(scene::Action::ResetGame))
+                    }
+                    _ => None,
+                }
+            },
+            (InputAction::Pause, ElementState::Pressed) => {
+                if self.game.phase == GamePhase::Playing {
+                    self.pause();
+                }
+                None
+            },
+            _ => None
+        }
+    }
+
+    /// Sends the scene what the gamepads did since the last frame.
+    fn steer_with_gamepads(&mut self) {
+        let actions = self.gamepads.poll();
+        let phase = self.game.phase;
+        if self.game.is_replay() || phase == GamePhase::Menu || phase == GamePhase::Paused {
+            // Paddles don't move in menus, they get the gamepads' speed once out of them
+            self.gamepads.forget_sent();
+            return;
+        }
+        let right_is_cpu = self.game.is_cpu_playing(Side::Right);
+        for action in actions {
+            match action {
+                scene::Action::RightPaddleSteer(_) | scene::Action::RightPaddleStop
+                    if right_is_cpu => {}
+                _ => self.game.handle_scene_action(action),
+            }
+        }
+    }
+
+    /// Fits the view of the court to the window, and aims the mouse paddle at
+    /// the height of the court under the cursor.
+    fn fit_camera_to_window(&mut self) {
+        let screen_size = self.graphics_manager.screen_size();
+        let [width, height] = screen_size;
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+        self.game.scene.camera.set_aspect_ratio(width / height);
+
+        if let (Some(mouse), Some(cursor)) = (&mut self.mouse, self.cursor) {
+            // Paddles move on the plane of the court, at depth zero
+            if let Some(point) = self.game.scene.camera.unproject(cursor, screen_size, 0.0) {
+                mouse.aim(point.y);
+            }
+        }
+    }
+
+    /// Sends the scene the speed taking the mouse paddle towards the cursor
+    /// during the next `delta_time` seconds.
+    fn steer_with_mouse(&mut self, delta_time: f32) {
+        let mouse = match &self.mouse {
+            Some(mouse) => mouse,
+            None => return,
+        };
+        if self.game.is_replay() || self.game.is_cpu_playing(mouse.side()) {
+            return;
+        }
+        if let Some(action) = mouse.update(&self.game.scene, delta_time) {
+            self.game.handle_scene_action(action);
+        }
+    }
+
+    /// Freezes the game and opens the pause menu.
+    fn pause(&mut self) {
+        // Keys released while the menu is open never reach the paddles
+        self.left_keys.clear();
+        self.right_keys.clear();
+        self.game.handle_scene_action(scene::Action::LeftPaddleStop);
+        self.game.handle_scene_action(scene::Action::RightPaddleStop);
+        self.menu.open(MenuScreen::Pause);
+        self.game.set_phase(GamePhase::Paused);
+    }
+
+    fn resume(&mut self) {
+        // The time spent in the pause menu must not reach the scene as one huge step
+        self.tick_counter.reset();
+        self.timestep.reset();
+        self.game.set_phase(GamePhase::Playing);
+    }
+
+    fn handle_menu_input(&mut self, keycode: VirtualKeyCode) -> Option<PongRustActions> {
+        let key = Key::from_keycode(keycode)?;
+        let input = MenuInput::from_key(key, &self.bindings)?;
+
+        match self.menu.handle_input(input, &mut self.game.settings)? {
+            MenuCommand::Play => {
+                self.game.set_phase(GamePhase::Start);
+                None
+            }
+            MenuCommand::Resume => {
+                self.resume();
+                None
+            }
+            MenuCommand::MainMenu => {
+                self.menu.open(MenuScreen::Main);
+                self.game.set_phase(GamePhase::Menu);
+                Some(PongRustActions::SceneAction(scene::Action::ResetGame))
+            }
+            MenuCommand::Quit => Some(PongRustActions::SystemAction(Action::Quit)),
+            MenuCommand::SettingsChanged => {
+                self.game.apply_settings(self.game.settings);
+                None
+            }
+            MenuCommand::SwapGamepads => {
+                self.gamepads.swap_sides();
+                None
+            }
+        }
+    }
+}
+
+/// Opens the game window described by `config` and plays `game` in it.
+///
+/// Only returns when the renderer can't be set up, otherwise the process exits
+/// when the window is closed.
+pub fn run(game: Game, config: &Config) -> Result<(), GraphicsError> {
+    let event_loop = EventLoop::new();
+    let graphics_manager =
+        GraphicsManager::new(&event_loop, &game.scene, &config.window, &config.graphics)?;
+    let pong_rust = PongRust {
+        graphics_manager,
+        game,
+        menu: Menu::new(),
+        bindings: config.controls.clone(),
+        left_keys: HeldKeys::default(),
+        right_keys: HeldKeys::default(),
+        gamepads: Gamepads::new(gamepad::system_backend(&config.gamepads), &config.gamepads),
+        mouse: config.mouse.paddle.map(MousePaddle::new),
+        cursor: None,
+        tick_counter: FPSLimiter::new(config.graphics.target_fps()),
+        timestep: FixedTimestep::new(config.gameplay.simulation_rate),
+        show_fps: config.graphics.show_fps,
+        has_failed: false,
+    };
+
+    pong_rust.main_loop(event_loop);
+    Ok(())
+}
+
+/// Lists the graphics cards the game could draw with, see `--list-devices`.
+pub fn list_devices(config: &Config) -> Result<(), GraphicsError> {
+    let event_loop = EventLoop::new();
+    graphics_manager::print_physical_devices(&event_loop, &config.graphics)
+}
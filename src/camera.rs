@@ -1,12 +1,136 @@
-use cgmath::Matrix4;
+use cgmath::{Deg, Matrix4, SquareMatrix, Vector3, Vector4};
 
+/// Where the court is seen from, and how it is projected on the window.
 pub struct Camera {
     pub view: Matrix4<f32>,
     pub proj: Matrix4<f32>,
+    fovy: Deg<f32>,
+    near: f32,
+    far: f32,
 }
 
 impl Camera {
-    pub fn new(view: Matrix4<f32>, proj: Matrix4<f32>) -> Self {
-        Self { view, proj }
+    /// Camera seeing `fovy` high, for a window `aspect_ratio` times wider than high.
+    pub fn perspective(
+        view: Matrix4<f32>,
+        fovy: Deg<f32>,
+        aspect_ratio: f32,
+        near: f32,
+        far: f32,
+    ) -> Self {
+        Self {
+            view,
+            proj: cgmath::perspective(fovy, aspect_ratio, near, far),
+            fovy,
+            near,
+            far,
+        }
+    }
+
+    /// Fits the projection to a window `aspect_ratio` times wider than high.
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        self.proj = cgmath::perspective(self.fovy, aspect_ratio, self.near, self.far);
+    }
+
+    /// Point of the plane at depth `plane_z` seen under the pixel `position` of a
+    /// window `screen_size` pixels large, if the view isn't parallel to the plane.
+    pub fn unproject(
+        &self,
+        position: [f32; 2],
+        screen_size: [f32; 2],
+        plane_z: f32,
+    ) -> Option<Vector3<f32>> {
+        let [width, height] = screen_size;
+        if width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        // Vulkan puts -1 at the top left of the window, like the pixels start there
+        let x = 2.0 * position[0] / width - 1.0;
+        let y = 2.0 * position[1] / height - 1.0;
+        let inverse = (self.proj * self.view).invert()?;
+        let world = |depth: f32| {
+            let point = inverse * Vector4::new(x, y, depth, 1.0);
+            point.truncate() / point.w
+        };
+
+        // The pixel covers a ray from the near plane to the far one
+        let near = world(-1.0);
+        let direction = world(1.0) - near;
+        if direction.z.abs() < f32::EPSILON {
+            return None;
+        }
+        Some(near + direction * ((plane_z - near.z) / direction.z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{InnerSpace, Point3, Transform};
+
+    const SCREEN: [f32; 2] = [800.0, 600.0];
+
+    fn camera() -> Camera {
+        Camera::perspective(
+            Matrix4::look_at(
+                Point3::new(0.0, 0.0, 10.0),
+                Point3::new(0.0, 0.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+            ),
+            Deg(45.0),
+            SCREEN[0] / SCREEN[1],
+            0.1,
+            10.0,
+        )
+    }
+
+    /// Pixel `point` is drawn at, the way the renderer projects it.
+    fn project(camera: &Camera, point: Vector3<f32>) -> [f32; 2] {
+        let ndc =
+            (camera.proj * camera.view).transform_point(Point3::new(point.x, point.y, point.z));
+        [
+            (ndc.x + 1.0) / 2.0 * SCREEN[0],
+            (ndc.y + 1.0) / 2.0 * SCREEN[1],
+        ]
+    }
+
+    fn assert_close(actual: Vector3<f32>, expected: Vector3<f32>) {
+        assert!(
+            (actual - expected).magnitude() < 1e-3,
+            "{:?} is not {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn centre_of_the_window_is_the_centre_of_the_court() {
+        let point = camera().unproject([400.0, 300.0], SCREEN, 0.0).unwrap();
+
+        assert_close(point, Vector3::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn unprojecting_undoes_the_projection() {
+        let camera = camera();
+        for point in &[Vector3::new(-4.0, 2.5, 0.0), Vector3::new(1.0, -3.0, 0.0)] {
+            let pixel = project(&camera, *point);
+
+            assert_close(camera.unproject(pixel, SCREEN, 0.0).unwrap(), *point);
+        }
+        // Lower on the window is further down the court, where y grows
+        assert!(camera.unproject([400.0, 500.0], SCREEN, 0.0).unwrap().y > 0.0);
+    }
+
+    #[test]
+    fn wider_windows_show_more_of_the_court() {
+        let mut camera = camera();
+        let edge = camera.unproject([800.0, 300.0], SCREEN, 0.0).unwrap().x;
+        camera.set_aspect_ratio(2.0);
+
+        let wide = [1200.0, 600.0];
+        let wide_edge = camera.unproject([1200.0, 300.0], wide, 0.0).unwrap().x;
+        assert!(wide_edge > edge);
+        assert!(camera.unproject([0.0, 0.0], [0.0, 0.0], 0.0).is_none());
     }
 }
@@ -0,0 +1,347 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// An option of the command line, as listed by `--help`.
+struct CliOption {
+    name: &'static str,
+    /// What the value is called in the help, `None` for flags
+    value: Option<&'static str>,
+    help: &'static str,
+}
+
+const OPTIONS: &[CliOption] = &[
+    CliOption {
+        name: "--width",
+        value: Some("<pixels>"),
+        help: "Width of the window",
+    },
+    CliOption {
+        name: "--height",
+        value: Some("<pixels>"),
+        help: "Height of the window",
+    },
+    CliOption {
+        name: "--fullscreen",
+        value: None,
+        help: "Cover the whole screen",
+    },
+    CliOption {
+        name: "--windowed",
+        value: None,
+        help: "Play in a window, even when the configuration says fullscreen",
+    },
+    CliOption {
+        name: "--validation",
+        value: None,
+        help: "Turn the Vulkan validation layers on",
+    },
+    CliOption {
+        name: "--no-validation",
+        value: None,
+        help: "Turn the Vulkan validation layers off",
+    },
+    CliOption {
+        name: "--gpu",
+        value: Some("<index|name>"),
+        help: "Graphics card to draw with, by its number in --list-devices or part of its name",
+    },
+    CliOption {
+        name: "--list-devices",
+        value: None,
+        help: "Print the graphics cards the game can see and exit",
+    },
+    CliOption {
+        name: "--mouse",
+        value: Some("<paddle>"),
+        help: "Paddle following the mouse cursor: left, right or none",
+    },
+    CliOption {
+        name: "--seed",
+        value: Some("<number>"),
+        help: "Seed of the game, a random one when it is not given",
+    },
+    CliOption {
+        name: "--game-mode",
+        value: Some("<mode>"),
+        help: "Rules of the match: classic or win-by-two",
+    },
+    CliOption {
+        name: "--opponent",
+        value: Some("<opponent>"),
+        help: "Who plays the right paddle: human, cpu-easy, cpu-normal or cpu-hard",
+    },
+    CliOption {
+        name: "--headless",
+        value: None,
+        help: "Play without a window, the computer on both paddles, and print the result",
+    },
+    CliOption {
+        name: "--ticks",
+        value: Some("<number>"),
+        help: "Simulation steps a headless run lasts",
+    },
+    CliOption {
+        name: "--replay",
+        value: Some("<path>"),
+        help: "Play a recorded game back",
+    },
+    CliOption {
+        name: "--record",
+        value: Some("<path>"),
+        help: "Record the game to a file",
+    },
+    CliOption {
+        name: "--config",
+        value: Some("<path>"),
+        help: "Configuration file to read instead of pong-rust.toml",
+    },
+    CliOption {
+        name: "--set",
+        value: Some("<section.key=value>"),
+        help: "Override a key of the configuration, can be repeated",
+    },
+    CliOption {
+        name: "--log-level",
+        value: Some("<filter>"),
+        help: "Messages shown: off, error, warn, info, debug or trace, then target=level pairs",
+    },
+    CliOption {
+        name: "--help",
+        value: None,
+        help: "Print this help and exit",
+    },
+];
+
+/// What was asked for on the command line.
+///
+/// Options that stand for a key of the configuration end up in `overrides`,
+/// in the order they were given, after the `--set` ones before them.
+#[derive(Debug, Default, PartialEq)]
+pub struct Cli {
+    pub config: Option<PathBuf>,
+    /// `section.key=value` overrides of the configuration
+    pub overrides: Vec<String>,
+    pub seed: Option<u64>,
+    pub headless: bool,
+    pub ticks: Option<u64>,
+    pub replay: Option<PathBuf>,
+    pub record: Option<PathBuf>,
+    pub list_devices: bool,
+    pub help: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+    Unknown(String),
+    MissingValue(&'static str),
+    InvalidValue {
+        option: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+    /// A value given to an option that doesn't take one
+    UnexpectedValue(&'static str),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Unknown(arg) => write!(f, "unknown option {}", arg),
+            CliError::MissingValue(option) => write!(f, "{} expects a value", option),
+            CliError::InvalidValue {
+                option,
+                value,
+                expected,
+            } => write!(f, "{} expects {}, not {}", option, expected, value),
+            CliError::UnexpectedValue(option) => write!(f, "{} doesn't take a value", option),
+        }
+    }
+}
+
+impl Cli {
+    /// Reads the arguments, without the name of the program.
+    ///
+    /// Values follow their option, as in `--seed 42`, or are joined to it, as in `--seed=42`.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Cli, CliError> {
+        let mut cli = Cli::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (name, joined_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let name = if name == "-h" { "--help" } else { name };
+            let option = OPTIONS
+                .iter()
+                .find(|option| option.name == name)
+                .ok_or_else(|| CliError::Unknown(arg.clone()))?;
+            let value = match (option.value, joined_value) {
+                (Some(_), Some(value)) => value,
+                (Some(_), None) => args.next().ok_or(CliError::MissingValue(option.name))?,
+                (None, Some(_)) => return Err(CliError::UnexpectedValue(option.name)),
+                (None, None) => String::new(),
+            };
+            cli.read(option.name, value)?;
+        }
+        Ok(cli)
+    }
+
+    fn read(&mut self, option: &'static str, value: String) -> Result<(), CliError> {
+        let invalid = |expected| CliError::InvalidValue {
+            option,
+            value: value.clone(),
+            expected,
+        };
+        match option {
+            "--width" => self.overrides.push(format!("window.width={}", value)),
+            "--height" => self.overrides.push(format!("window.height={}", value)),
+            "--fullscreen" => self.overrides.push("window.fullscreen=true".to_string()),
+            "--windowed" => self.overrides.push("window.fullscreen=false".to_string()),
+            "--validation" => self.overrides.push("graphics.validation=true".to_string()),
+            "--no-validation" => self.overrides.push("graphics.validation=false".to_string()),
+            "--gpu" => self.overrides.push(format!("graphics.gpu={}", value)),
+            "--game-mode" => self.overrides.push(format!("gameplay.game_mode={}", value)),
+            "--opponent" => self.overrides.push(format!("gameplay.opponent={}", value)),
+            "--mouse" => self.overrides.push(format!("mouse.paddle={}", value)),
+            "--set" => self.overrides.push(value),
+            "--list-devices" => self.list_devices = true,
+            "--seed" => {
+                self.seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| invalid("a non-negative integer"))?,
+                )
+            }
+            "--headless" => self.headless = true,
+            "--ticks" => {
+                self.ticks = Some(value.parse().map_err(|_| invalid("a positive integer"))?)
+            }
+            "--replay" => self.replay = Some(PathBuf::from(value)),
+            "--record" => self.record = Some(PathBuf::from(value)),
+            "--config" => self.config = Some(PathBuf::from(value)),
+            "--log-level" => self.overrides.push(format!("logging.level={}", value)),
+            "--help" => self.help = true,
+            _ => unreachable!("{} is listed without being read", option),
+        }
+        Ok(())
+    }
+}
+
+/// The usage of the game followed by every option.
+pub fn help() -> String {
+    let mut help = String::from("Usage: pong-rust [options]\n\nOptions:\n");
+    let columns: Vec<(String, &str)> = OPTIONS
+        .iter()
+        .map(|option| match option.value {
+            Some(value) => (format!("{} {}", option.name, value), option.help),
+            None => (option.name.to_string(), option.help),
+        })
+        .collect();
+    let width = columns
+        .iter()
+        .map(|(usage, _)| usage.len())
+        .max()
+        .unwrap_or(0);
+    for (usage, text) in columns {
+        help.push_str(&format!("  {:width$}  {}\n", usage, text, width = width));
+    }
+    help
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, CliError> {
+        Cli::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn options_are_read() {
+        let cli = parse(&[
+            "--seed",
+            "42",
+            "--headless",
+            "--ticks=600",
+            "--record",
+            "game.replay",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.seed, Some(42));
+        assert!(cli.headless);
+        assert_eq!(cli.ticks, Some(600));
+        assert_eq!(cli.record, Some(PathBuf::from("game.replay")));
+        assert_eq!(cli.replay, None);
+        assert!(!cli.list_devices);
+    }
+
+    #[test]
+    fn configuration_options_become_overrides_in_order() {
+        let cli = parse(&[
+            "--set",
+            "window.width=640",
+            "--width",
+            "1024",
+            "--fullscreen",
+            "--no-validation",
+            "--gpu",
+            "geforce",
+            "--game-mode",
+            "win-by-two",
+            "--opponent",
+            "cpu-hard",
+            "--mouse",
+            "left",
+            "--log-level",
+            "info,vulkan=debug",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            cli.overrides,
+            [
+                "window.width=640",
+                "window.width=1024",
+                "window.fullscreen=true",
+                "graphics.validation=false",
+                "graphics.gpu=geforce",
+                "gameplay.game_mode=win-by-two",
+                "gameplay.opponent=cpu-hard",
+                "mouse.paddle=left",
+                "logging.level=info,vulkan=debug",
+            ]
+        );
+    }
+
+    #[test]
+    fn mistakes_are_reported() {
+        assert_eq!(
+            parse(&["--speed", "3"]),
+            Err(CliError::Unknown("--speed".to_string()))
+        );
+        assert_eq!(parse(&["--seed"]), Err(CliError::MissingValue("--seed")));
+        assert_eq!(
+            parse(&["--seed", "-1"]).unwrap_err().to_string(),
+            "--seed expects a non-negative integer, not -1"
+        );
+        assert_eq!(
+            parse(&["--headless=yes"]),
+            Err(CliError::UnexpectedValue("--headless"))
+        );
+    }
+
+    #[test]
+    fn help_lists_every_option() {
+        let help = help();
+        for option in OPTIONS {
+            assert!(help.contains(option.name), "{} is missing", option.name);
+            let arg = match option.value {
+                Some(_) => format!("{}=1", option.name),
+                None => option.name.to_string(),
+            };
+            assert!(Cli::parse(vec![arg]).is_ok(), "{} is not read", option.name);
+        }
+        assert!(parse(&["-h"]).unwrap().help);
+    }
+}
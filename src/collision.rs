@@ -0,0 +1,209 @@
+use cgmath::Vector2;
+
+/// Small tolerance used to accept contacts that floating point error placed
+/// slightly behind a face.
+const EPSILON: f32 = 1e-4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+/// An axis-aligned face of a box, seen from the outside.
+///
+/// `position` is the coordinate of the face along `axis`, `normal` is either
+/// `1.0` or `-1.0` and points away from the box, and `span` is the extent of the
+/// face along the other axis. The face moves with `velocity` during a sweep.
+#[derive(Clone, Copy, Debug)]
+pub struct Face {
+    pub axis: Axis,
+    pub position: f32,
+    pub normal: f32,
+    pub span: (f32, f32),
+    pub velocity: Vector2<f32>,
+}
+
+impl Face {
+    pub fn new(
+        axis: Axis,
+        position: f32,
+        normal: f32,
+        span: (f32, f32),
+        velocity: Vector2<f32>,
+    ) -> Self {
+        Self {
+            axis,
+            position,
+            normal,
+            span,
+            velocity,
+        }
+    }
+}
+
+/// Splits a vector into its components along and across `axis`.
+fn split(vector: Vector2<f32>, axis: Axis) -> (f32, f32) {
+    match axis {
+        Axis::X => (vector.x, vector.y),
+        Axis::Y => (vector.y, vector.x),
+    }
+}
+
+/// Time at which a square of half size `radius`, centred at `position` and moving
+/// with `velocity`, first touches `face`, if that happens within `max_time`.
+///
+/// Only contacts from the side the normal points to are reported, so a square
+/// that is already moving away from a face is never caught by it again.
+pub fn time_of_impact(
+    position: Vector2<f32>,
+    velocity: Vector2<f32>,
+    radius: f32,
+    face: &Face,
+    max_time: f32,
+) -> Option<f32> {
+    let (position_along, position_across) = split(position, face.axis);
+    let (_, velocity_across) = split(velocity, face.axis);
+    let (relative_along, _) = split(velocity - face.velocity, face.axis);
+    let (_, face_across) = split(face.velocity, face.axis);
+
+    // Distance from the square to the face, measured along the normal
+    let gap = (position_along - (face.position + face.normal * radius)) * face.normal;
+    let closing_speed = -relative_along * face.normal;
+    if gap < -EPSILON || closing_speed <= 0.0 {
+        return None;
+    }
+
+    let time = (gap / closing_speed).max(0.0);
+    if time > max_time {
+        return None;
+    }
+
+    // The square must overlap the face (grown by its radius) at the contact time
+    let across = position_across + velocity_across * time;
+    let min = face.span.0 + face_across * time - radius;
+    let max = face.span.1 + face_across * time + radius;
+    if across < min || across > max {
+        return None;
+    }
+
+    Some(time)
+}
+
+/// Finds the first face hit within `max_time`, returning its index and the time of impact.
+pub fn earliest_hit(
+    position: Vector2<f32>,
+    velocity: Vector2<f32>,
+    radius: f32,
+    faces: &[Face],
+    max_time: f32,
+) -> Option<(usize, f32)> {
+    faces
+        .iter()
+        .enumerate()
+        .filter_map(|(index, face)| {
+            time_of_impact(position, velocity, radius, face, max_time).map(|time| (index, time))
+        })
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+}
+
+/// Velocity of a body after bouncing off `face`.
+///
+/// The component along the face normal is mirrored relative to the face, so a
+/// moving face hands its own speed over to the body.
+pub fn reflect(velocity: Vector2<f32>, face: &Face) -> Vector2<f32> {
+    match face.axis {
+        Axis::X => Vector2::new(2.0 * face.velocity.x - velocity.x, velocity.y),
+        Axis::Y => Vector2::new(velocity.x, 2.0 * face.velocity.y - velocity.y),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Zero;
+
+    fn vertical_face(position: f32, normal: f32) -> Face {
+        Face::new(Axis::X, position, normal, (-1.0, 1.0), Vector2::zero())
+    }
+
+    #[test]
+    fn hit_is_found_at_contact_time() {
+        let face = vertical_face(0.0, 1.0);
+        let time = time_of_impact(
+            Vector2::new(2.0, 0.0),
+            Vector2::new(-1.0, 0.0),
+            0.5,
+            &face,
+            10.0,
+        );
+        assert_eq!(time, Some(1.5));
+    }
+
+    #[test]
+    fn hit_is_ignored_when_moving_away() {
+        let face = vertical_face(0.0, 1.0);
+        let time = time_of_impact(
+            Vector2::new(0.5, 0.0),
+            Vector2::new(1.0, 0.0),
+            0.5,
+            &face,
+            10.0,
+        );
+        assert_eq!(time, None);
+    }
+
+    #[test]
+    fn hit_is_ignored_outside_of_span() {
+        let face = vertical_face(0.0, 1.0);
+        let time = time_of_impact(
+            Vector2::new(2.0, 1.6),
+            Vector2::new(-1.0, 0.0),
+            0.5,
+            &face,
+            10.0,
+        );
+        assert_eq!(time, None);
+    }
+
+    #[test]
+    fn hit_is_ignored_beyond_max_time() {
+        let face = vertical_face(0.0, 1.0);
+        let time = time_of_impact(
+            Vector2::new(2.0, 0.0),
+            Vector2::new(-1.0, 0.0),
+            0.5,
+            &face,
+            1.0,
+        );
+        assert_eq!(time, None);
+    }
+
+    #[test]
+    fn moving_span_is_tracked_over_time() {
+        let mut face = vertical_face(0.0, 1.0);
+        face.velocity = Vector2::new(0.0, 2.0);
+        // the ball is below the face when the sweep starts, but the face slides down to it
+        let time = time_of_impact(
+            Vector2::new(2.0, 3.0),
+            Vector2::new(-1.0, 0.0),
+            0.5,
+            &face,
+            10.0,
+        );
+        assert_eq!(time, Some(1.5));
+    }
+
+    #[test]
+    fn earliest_hit_picks_closest_face() {
+        let faces = [vertical_face(-4.0, 1.0), vertical_face(-1.0, 1.0)];
+        let hit = earliest_hit(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(-1.0, 0.0),
+            0.0,
+            &faces,
+            10.0,
+        );
+        assert_eq!(hit, Some((1, 1.0)));
+    }
+}
@@ -0,0 +1,704 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+
+use crate::input::KeyBindings;
+use crate::logging::LogFilter;
+use crate::paddle::PaddleMotion;
+use crate::rally::{Progression, RallySpeed};
+use crate::replay::Replay;
+use crate::scene::{Court, DEFAULT_BALL_SPEED, DEFAULT_PADDLE_SPEED};
+use crate::score::{MatchRules, Side};
+use crate::settings::{
+    GameMode, Opponent, Settings, BALL_SPEED_RANGE, PADDLE_SPEED_RANGE, TARGET_SCORE_RANGE,
+};
+use crate::timestep::DEFAULT_SIMULATION_RATE;
+
+/// Read from the working directory when no other configuration file is given
+pub const DEFAULT_CONFIG_PATH: &str = "pong-rust.toml";
+
+/// Everything that can be tuned without rebuilding the game.
+///
+/// Missing sections and keys keep their default value.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub gameplay: GameplayConfig,
+    pub court: Court,
+    pub left_paddle: PaddleMotion,
+    pub right_paddle: PaddleMotion,
+    pub rally: RallySpeed,
+    pub window: WindowConfig,
+    pub graphics: GraphicsConfig,
+    pub controls: KeyBindings,
+    pub gamepads: GamepadConfig,
+    pub mouse: MouseConfig,
+    pub logging: LoggingConfig,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameplayConfig {
+    pub ball_speed: f32,
+    pub paddle_speed: f32,
+    pub target_score: u32,
+    #[serde(deserialize_with = "game_mode")]
+    pub game_mode: GameMode,
+    #[serde(deserialize_with = "opponent")]
+    pub opponent: Opponent,
+    /// Simulation steps per second
+    pub simulation_rate: f32,
+}
+
+impl Default for GameplayConfig {
+    fn default() -> Self {
+        Self {
+            ball_speed: DEFAULT_BALL_SPEED,
+            paddle_speed: DEFAULT_PADDLE_SPEED,
+            target_score: MatchRules::default().target_score,
+            game_mode: GameMode::Classic,
+            opponent: Opponent::Human,
+            simulation_rate: DEFAULT_SIMULATION_RATE,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowConfig {
+    pub width: u32,
+    pub height: u32,
+    /// Covers the whole primary monitor instead of opening a window of the size above
+    pub fullscreen: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 600,
+            fullscreen: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GraphicsConfig {
+    /// Turns the Vulkan validation layers on
+    pub validation: bool,
+    pub show_fps: bool,
+    /// Frame rate the game is held to, `0` to draw frames as fast as possible
+    pub target_fps: u32,
+    pub max_frames_in_flight: usize,
+    /// Graphics card to draw with, the first suitable one when there is no choice
+    pub gpu: Option<DeviceChoice>,
+}
+
+impl Default for GraphicsConfig {
+    fn default() -> Self {
+        Self {
+            validation: true,
+            show_fps: true,
+            target_fps: 144,
+            max_frames_in_flight: 2,
+            gpu: None,
+        }
+    }
+}
+
+impl GraphicsConfig {
+    pub fn target_fps(&self) -> Option<u32> {
+        Some(self.target_fps).filter(|fps| *fps > 0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GamepadConfig {
+    /// Looks for gamepads, and keeps looking for the ones plugged in later
+    pub enabled: bool,
+    /// Part of the stick travel around its centre that leaves the paddle still
+    pub dead_zone: f32,
+    /// Number of the vertical axis of the stick, as the driver reports it
+    pub stick_axis: u8,
+    /// Number of the vertical axis of the D-pad
+    pub dpad_axis: u8,
+    /// Gamepad steering the left paddle, the first one plugged in when there is no choice
+    pub left: Option<DeviceChoice>,
+    pub right: Option<DeviceChoice>,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dead_zone: 0.2,
+            stick_axis: 1,
+            dpad_axis: 7,
+            left: None,
+            right: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MouseConfig {
+    /// Paddle following the height of the cursor, `none` to leave both to keys and gamepads
+    #[serde(deserialize_with = "mouse_paddle")]
+    pub paddle: Option<Side>,
+}
+
+/// A graphics card or a gamepad, given by its position among the devices or by part of its name.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum DeviceChoice {
+    Index(usize),
+    Name(String),
+}
+
+impl DeviceChoice {
+    /// Whether the device at `index`, called `name`, is the one chosen.
+    pub fn matches(&self, index: usize, name: &str) -> bool {
+        match self {
+            DeviceChoice::Index(chosen) => *chosen == index,
+            DeviceChoice::Name(chosen) => name.to_lowercase().contains(&chosen.to_lowercase()),
+        }
+    }
+}
+
+impl fmt::Display for DeviceChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeviceChoice::Index(index) => write!(f, "device {}", index),
+            DeviceChoice::Name(name) => write!(f, "\"{}\"", name),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    /// Messages written to the standard error, see `LogFilter`
+    #[serde(deserialize_with = "log_filter")]
+    pub level: LogFilter,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    /// The configuration is not valid TOML or has values of the wrong type,
+    /// `source` names the file or the command line it comes from
+    Parse {
+        source: String,
+        error: toml::de::Error,
+    },
+    /// A `key=value` override that could not be applied
+    Override {
+        text: String,
+        reason: String,
+    },
+    Invalid {
+        key: &'static str,
+        reason: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            ConfigError::Parse { source, error } => write!(f, "{}: {}", source, error),
+            ConfigError::Override { text, reason } => write!(f, "{}: {}", text, reason),
+            ConfigError::Invalid { key, reason } => write!(f, "{} {}", key, reason),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the configuration from `path`, or from `DEFAULT_CONFIG_PATH` when
+    /// there is one, then applies the `section.key=value` `overrides` in order.
+    pub fn load(path: Option<&Path>, overrides: &[String]) -> Result<Config, ConfigError> {
+        let path = match path {
+            Some(path) => Some(path.to_path_buf()),
+            None => Some(PathBuf::from(DEFAULT_CONFIG_PATH)).filter(|path| path.exists()),
+        };
+        let (table, source) = match &path {
+            Some(path) => {
+                let text = std::fs::read_to_string(path).map_err(|error| ConfigError::Io {
+                    path: path.clone(),
+                    error,
+                })?;
+                (
+                    parse_toml(&text, &path.display().to_string())?,
+                    path.display().to_string(),
+                )
+            }
+            None => (
+                toml::Value::Table(Default::default()),
+                "command line".to_string(),
+            ),
+        };
+
+        Config::from_toml(table, overrides, &source)
+    }
+
+    fn from_toml(
+        mut table: toml::Value,
+        overrides: &[String],
+        source: &str,
+    ) -> Result<Config, ConfigError> {
+        for text in overrides {
+            apply_override(&mut table, text)?;
+        }
+        let config: Config = table.try_into().map_err(|error| ConfigError::Parse {
+            source: source.to_string(),
+            error,
+        })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_settings(&self.settings())?;
+        check_range(
+            "gameplay.simulation_rate",
+            self.gameplay.simulation_rate,
+            (30.0, 1000.0),
+        )?;
+
+        // The walls must stay in view, and the ball must be able to get past the paddles
+        check_range("court.wall_distance", self.court.wall_distance, (1.5, 4.0))?;
+        check_range("court.out_of_bounds", self.court.out_of_bounds, (4.5, 10.0))?;
+        check_motion(
+            &self.left_paddle,
+            [
+                "left_paddle.acceleration",
+                "left_paddle.deceleration",
+                "left_paddle.max_speed",
+                "left_paddle.bounce",
+                "left_paddle.english",
+            ],
+        )?;
+        check_motion(
+            &self.right_paddle,
+            [
+                "right_paddle.acceleration",
+                "right_paddle.deceleration",
+                "right_paddle.max_speed",
+                "right_paddle.bounce",
+                "right_paddle.english",
+            ],
+        )?;
+        check_rally(&self.rally)?;
+
+        check_range("window.width", self.window.width, (200, 16384))?;
+        check_range("window.height", self.window.height, (200, 16384))?;
+        check_range(
+            "graphics.max_frames_in_flight",
+            self.graphics.max_frames_in_flight,
+            (1, 4),
+        )?;
+
+        check_range("gamepads.dead_zone", self.gamepads.dead_zone, (0.0, 0.9))?;
+
+        // A key bound twice would only ever do one of its actions
+        if let Some(conflict) = self.controls.conflicts().first() {
+            return Err(ConfigError::Invalid {
+                key: "controls",
+                reason: format!("has a conflict: {}", conflict),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The gameplay options players start with.
+    pub fn settings(&self) -> Settings {
+        Settings {
+            ball_speed: self.gameplay.ball_speed,
+            paddle_speed: self.gameplay.paddle_speed,
+            target_score: self.gameplay.target_score,
+            game_mode: self.gameplay.game_mode,
+            opponent: self.gameplay.opponent,
+        }
+    }
+
+    /// Takes the gameplay configuration `replay` was recorded with, which must
+    /// be as valid as one read from a file.
+    pub fn use_replay(&mut self, replay: &Replay) -> Result<(), ConfigError> {
+        let settings = &replay.settings;
+        self.gameplay.ball_speed = settings.ball_speed;
+        self.gameplay.paddle_speed = settings.paddle_speed;
+        self.gameplay.target_score = settings.target_score;
+        self.gameplay.game_mode = settings.game_mode;
+        self.gameplay.opponent = settings.opponent;
+        self.gameplay.simulation_rate = replay.simulation_rate;
+        self.court = replay.court;
+        self.left_paddle = replay.left_paddle;
+        self.right_paddle = replay.right_paddle;
+        self.rally = replay.rally.clone();
+
+        self.validate()
+    }
+}
+
+fn parse_toml(text: &str, source: &str) -> Result<toml::Value, ConfigError> {
+    text.parse().map_err(|error| ConfigError::Parse {
+        source: source.to_string(),
+        error,
+    })
+}
+
+fn check_range<T>(key: &'static str, value: T, (min, max): (T, T)) -> Result<(), ConfigError>
+where
+    T: PartialOrd + fmt::Display,
+{
+    if value >= min && value <= max {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid {
+            key,
+            reason: format!("must be between {} and {}, not {}", min, max, value),
+        })
+    }
+}
+
+/// Checks the gameplay `settings`, named after their keys in the `gameplay` table.
+pub(crate) fn check_settings(settings: &Settings) -> Result<(), ConfigError> {
+    check_range("gameplay.ball_speed", settings.ball_speed, BALL_SPEED_RANGE)?;
+    check_range(
+        "gameplay.paddle_speed",
+        settings.paddle_speed,
+        PADDLE_SPEED_RANGE,
+    )?;
+    check_range(
+        "gameplay.target_score",
+        settings.target_score,
+        TARGET_SCORE_RANGE,
+    )
+}
+
+/// Checks the fields of a paddle `motion`, named by `keys` in the order they are declared.
+fn check_motion(motion: &PaddleMotion, keys: [&'static str; 5]) -> Result<(), ConfigError> {
+    check_range(keys[0], motion.acceleration, (0.0, 100.0))?;
+    check_range(keys[1], motion.deceleration, (0.0, 100.0))?;
+    if let Some(max_speed) = motion.max_speed {
+        check_range(keys[2], max_speed, PADDLE_SPEED_RANGE)?;
+    }
+    check_range(keys[3], motion.bounce, (0.0, 1.0))?;
+    check_range(keys[4], motion.english, (0.0, 1.0))
+}
+
+fn check_rally(rally: &RallySpeed) -> Result<(), ConfigError> {
+    check_range("rally.increment", rally.increment, (0.0, 5.0))?;
+    check_range("rally.factor", rally.factor, (1.0, 2.0))?;
+    // Steps are only needed when the progression goes through them
+    if rally.progression == Progression::Steps && rally.steps.is_empty() {
+        return Err(ConfigError::Invalid {
+            key: "rally.steps",
+            reason: "needs at least one speed".to_string(),
+        });
+    }
+    for speed in &rally.steps {
+        check_range("rally.steps", *speed, (BALL_SPEED_RANGE.0, 50.0))?;
+    }
+    check_range(
+        "rally.max_speed",
+        rally.max_speed,
+        (BALL_SPEED_RANGE.1, 50.0),
+    )
+}
+
+/// Sets the value of `text`, written `section.key=value`, in `table`.
+fn apply_override(table: &mut toml::Value, text: &str) -> Result<(), ConfigError> {
+    let error = |reason: &str| ConfigError::Override {
+        text: text.to_string(),
+        reason: reason.to_string(),
+    };
+    let (key, value) = text
+        .split_once('=')
+        .ok_or_else(|| error("expected section.key=value"))?;
+    let (section, key) = key
+        .trim()
+        .split_once('.')
+        .ok_or_else(|| error("expected section.key=value"))?;
+
+    // Values are read as in the file, unquoted ones that aren't numbers or booleans as strings
+    let value = value.trim();
+    let value = format!("value = {}", value)
+        .parse::<toml::Value>()
+        .ok()
+        .and_then(|parsed| parsed.get("value").cloned())
+        .unwrap_or_else(|| toml::Value::String(value.to_string()));
+
+    let section = table
+        .as_table_mut()
+        .ok_or_else(|| error("the configuration is not a table"))?
+        .entry(section.to_string())
+        .or_insert_with(|| toml::Value::Table(Default::default()))
+        .as_table_mut()
+        .ok_or_else(|| error("not a section"))?;
+    section.insert(key.to_string(), value);
+    Ok(())
+}
+
+fn game_mode<'de, D: Deserializer<'de>>(deserializer: D) -> Result<GameMode, D::Error> {
+    let id = String::deserialize(deserializer)?;
+    GameMode::from_id(&id).ok_or_else(|| {
+        de::Error::custom(format!(
+            "unknown game mode `{}`, expected `classic` or `win-by-two`",
+            id
+        ))
+    })
+}
+
+fn log_filter<'de, D: Deserializer<'de>>(deserializer: D) -> Result<LogFilter, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(de::Error::custom)
+}
+
+fn mouse_paddle<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Side>, D::Error> {
+    let id = String::deserialize(deserializer)?;
+    match id.as_str() {
+        "none" => Ok(None),
+        "left" => Ok(Some(Side::Left)),
+        "right" => Ok(Some(Side::Right)),
+        _ => Err(de::Error::custom(format!(
+            "unknown paddle `{}`, expected `none`, `left` or `right`",
+            id
+        ))),
+    }
+}
+
+fn opponent<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Opponent, D::Error> {
+    let id = String::deserialize(deserializer)?;
+    Opponent::from_id(&id).ok_or_else(|| {
+        let ids: Vec<String> = Opponent::ALL
+            .iter()
+            .map(|opponent| format!("`{}`", opponent.id()))
+            .collect();
+        de::Error::custom(format!(
+            "unknown opponent `{}`, expected one of {}",
+            id,
+            ids.join(", ")
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::Difficulty;
+    use crate::input::{InputAction, Key};
+
+    fn parse(text: &str, overrides: &[&str]) -> Result<Config, ConfigError> {
+        let overrides: Vec<String> = overrides.iter().map(|text| text.to_string()).collect();
+        Config::from_toml(parse_toml(text, "test")?, &overrides, "test")
+    }
+
+    #[test]
+    fn missing_keys_keep_their_defaults() {
+        let config = parse("[gameplay]\nball_speed = 6.0\n", &[]).unwrap();
+
+        assert_eq!(config.gameplay.ball_speed, 6.0);
+        assert_eq!(config.gameplay.paddle_speed, DEFAULT_PADDLE_SPEED);
+        assert_eq!(config.window, WindowConfig::default());
+        assert_eq!(parse("", &[]).unwrap(), Config::default());
+    }
+
+    #[test]
+    fn named_values_are_read() {
+        let config = parse(
+            "[gameplay]\ngame_mode = \"win-by-two\"\nopponent = \"cpu-hard\"\n",
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(config.settings().game_mode, GameMode::WinByTwo);
+        assert_eq!(config.settings().opponent, Opponent::Cpu(Difficulty::Hard));
+        assert!(parse("[gameplay]\nopponent = \"robot\"\n", &[]).is_err());
+    }
+
+    #[test]
+    fn overrides_replace_single_keys() {
+        let config = parse(
+            "[window]\nwidth = 1024\n",
+            &[
+                "window.height=768",
+                "gameplay.opponent=cpu-easy",
+                "graphics.validation = false",
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(config.window.width, 1024);
+        assert_eq!(config.window.height, 768);
+        assert_eq!(config.gameplay.opponent, Opponent::Cpu(Difficulty::Easy));
+        assert!(!config.graphics.validation);
+        assert!(matches!(
+            parse("", &["width=1024"]),
+            Err(ConfigError::Override { .. })
+        ));
+    }
+
+    #[test]
+    fn out_of_range_values_are_rejected() {
+        let error = parse("[court]\nwall_distance = 9.0\n", &[]).unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::Invalid {
+                key: "court.wall_distance",
+                ..
+            }
+        ));
+        assert!(parse("", &["gameplay.target_score=0"]).is_err());
+    }
+
+    #[test]
+    fn gpu_is_chosen_by_index_or_name() {
+        let by_index = parse("", &["graphics.gpu=1"]).unwrap().graphics.gpu.unwrap();
+        let by_name = parse("", &["graphics.gpu=geforce"]).unwrap().graphics.gpu.unwrap();
+
+        assert_eq!(by_index, DeviceChoice::Index(1));
+        assert!(by_index.matches(1, "llvmpipe"));
+        assert!(!by_index.matches(0, "llvmpipe"));
+        assert!(by_name.matches(0, "NVIDIA GeForce GTX 1060"));
+        assert!(!by_name.matches(0, "AMD Radeon RX 580"));
+        assert_eq!(parse("", &[]).unwrap().graphics.gpu, None);
+    }
+
+    #[test]
+    fn key_bindings_are_read_and_checked() {
+        let config = parse(
+            "[controls]\nleft_paddle_up = [\"Z\", \"up\"]\n",
+            &["controls.left_paddle_down=[\"S\"]"],
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.controls.keys(InputAction::LeftPaddleUp),
+            [Key::Z, Key::Up]
+        );
+        assert_eq!(
+            config.controls.action_of(Key::S),
+            Some(InputAction::LeftPaddleDown)
+        );
+        assert!(parse("[controls]\nserve = [\"Spacebar\"]\n", &[]).is_err());
+        assert!(matches!(
+            parse("[controls]\npause = [\"W\"]\n", &[]),
+            Err(ConfigError::Invalid {
+                key: "controls",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn paddle_motion_is_read_for_each_paddle() {
+        let config = parse(
+            "[left_paddle]\nacceleration = 12.0\nmax_speed = 3.0\n",
+            &["right_paddle.english=0.25"],
+        )
+        .unwrap();
+
+        assert_eq!(config.left_paddle.acceleration, 12.0);
+        assert_eq!(config.left_paddle.max_speed, Some(3.0));
+        assert_eq!(config.left_paddle.english, 0.0);
+        assert_eq!(config.right_paddle.english, 0.25);
+        assert_eq!(config.right_paddle.max_speed, None);
+        assert!(matches!(
+            parse("", &["right_paddle.bounce=1.5"]),
+            Err(ConfigError::Invalid {
+                key: "right_paddle.bounce",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn rally_progression_is_read() {
+        let config = parse(
+            "[rally]\nprogression = \"linear\"\nincrement = 0.5\n",
+            &["rally.max_speed=10"],
+        )
+        .unwrap();
+        assert_eq!(config.rally.progression, Progression::Linear);
+        assert_eq!(config.rally.increment, 0.5);
+        assert_eq!(config.rally.max_speed, 10.0);
+
+        let config = parse("", &["rally.progression=steps", "rally.steps=[4.5, 5.0]"]).unwrap();
+        assert_eq!(config.rally.steps, [4.5, 5.0]);
+        assert!(parse("", &["rally.factor=0.5"]).is_err());
+        assert!(parse("", &["rally.progression=steps", "rally.steps=[]"]).is_err());
+        assert!(parse("", &["rally.progression=quadratic"]).is_err());
+    }
+
+    #[test]
+    fn mouse_paddle_is_read() {
+        let config = parse("[mouse]\npaddle = \"right\"\n", &[]).unwrap();
+
+        assert_eq!(config.mouse.paddle, Some(Side::Right));
+        assert_eq!(
+            parse("", &["mouse.paddle=none"]).unwrap().mouse.paddle,
+            None
+        );
+        assert_eq!(parse("", &[]).unwrap().mouse.paddle, None);
+        assert!(parse("", &["mouse.paddle=both"]).is_err());
+    }
+
+    #[test]
+    fn log_level_is_read() {
+        let config = parse("[logging]\nlevel = \"debug,vulkan=warn\"\n", &[]).unwrap();
+        let level = &config.logging.level;
+
+        assert_eq!(level.level_of("vulkan"), log::LevelFilter::Warn);
+        assert_eq!(level.level_of("pong_rust"), log::LevelFilter::Debug);
+        assert!(parse("", &["logging.level=loud"]).is_err());
+    }
+
+    #[test]
+    fn replays_bring_a_checked_configuration() {
+        let mut replay = Replay {
+            seed: 3,
+            simulation_rate: 60.0,
+            settings: Settings {
+                target_score: 5,
+                ..Settings::default()
+            },
+            court: Court::default(),
+            left_paddle: PaddleMotion::default(),
+            right_paddle: PaddleMotion::default(),
+            rally: RallySpeed::default(),
+            events: vec![],
+        };
+        let mut config = Config::default();
+        config.use_replay(&replay).unwrap();
+        assert_eq!(config.settings(), replay.settings);
+        assert_eq!(config.gameplay.simulation_rate, 60.0);
+
+        replay.court.out_of_bounds = 0.1;
+        assert!(matches!(
+            config.use_replay(&replay),
+            Err(ConfigError::Invalid {
+                key: "court.out_of_bounds",
+                ..
+            })
+        ));
+        replay.court = Court::default();
+        replay.simulation_rate = 0.0001;
+        assert!(config.use_replay(&replay).is_err());
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(parse("[window]\nwitdh = 1024\n", &[]).is_err());
+    }
+}
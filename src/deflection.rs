@@ -0,0 +1,111 @@
+use cgmath::{Angle, Deg, Rad, Vector2};
+use num::clamp;
+
+/// Shape of the mapping between where the ball hits a paddle and how much it is deflected.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeflectionCurve {
+    /// Angle grows linearly from the centre of the paddle to its ends
+    Linear,
+    /// Angle grows with the offset raised to the given exponent, so values above
+    /// one keep the centre of the paddle flat and make its ends sharp
+    Power(f32),
+    /// Paddle split in the given number of bands, each with a fixed angle, like the arcade original
+    Segments(u32),
+}
+
+/// How a paddle sends the ball back depending on where it was hit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Deflection {
+    pub curve: DeflectionCurve,
+    /// Angle from the horizontal when the ball hits the very end of a paddle
+    pub max_angle: Deg<f32>,
+}
+
+impl Default for Deflection {
+    fn default() -> Self {
+        Self {
+            curve: DeflectionCurve::Linear,
+            max_angle: Deg(60.0),
+        }
+    }
+}
+
+impl Deflection {
+    /// Outgoing angle for a hit at `offset`, where `-1.0` and `1.0` are the ends of the paddle.
+    pub fn angle(&self, offset: f32) -> Deg<f32> {
+        let offset = clamp(offset, -1.0, 1.0);
+        let factor = match self.curve {
+            DeflectionCurve::Linear => offset,
+            DeflectionCurve::Power(exponent) => offset.signum() * offset.abs().powf(exponent),
+            DeflectionCurve::Segments(count) => {
+                let count = count.max(1) as f32;
+                // Index of the band, from 0 to count - 1, mapped back into [-1, 1]
+                let band = ((offset + 1.0) / 2.0 * count).floor().min(count - 1.0);
+                if count > 1.0 {
+                    band / (count - 1.0) * 2.0 - 1.0
+                } else {
+                    0.0
+                }
+            }
+        };
+
+        self.max_angle * factor
+    }
+
+    /// Velocity of a ball sent away from a paddle with the given `speed`.
+    ///
+    /// `direction` is `1.0` when the ball leaves towards positive X and `-1.0` otherwise.
+    pub fn velocity(&self, offset: f32, speed: f32, direction: f32) -> Vector2<f32> {
+        let angle: Rad<f32> = self.angle(offset).into();
+        Vector2::new(direction * speed * angle.cos(), speed * angle.sin())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::InnerSpace;
+
+    #[test]
+    fn centre_hit_goes_straight() {
+        let deflection = Deflection::default();
+        assert_eq!(deflection.angle(0.0), Deg(0.0));
+    }
+
+    #[test]
+    fn end_hit_uses_max_angle() {
+        let deflection = Deflection::default();
+        assert_eq!(deflection.angle(1.0), Deg(60.0));
+        assert_eq!(deflection.angle(-3.0), Deg(-60.0));
+    }
+
+    #[test]
+    fn power_curve_flattens_the_centre() {
+        let deflection = Deflection {
+            curve: DeflectionCurve::Power(2.0),
+            max_angle: Deg(40.0),
+        };
+        assert_eq!(deflection.angle(0.5), Deg(10.0));
+        assert_eq!(deflection.angle(-0.5), Deg(-10.0));
+    }
+
+    #[test]
+    fn segments_snap_to_bands() {
+        let deflection = Deflection {
+            curve: DeflectionCurve::Segments(3),
+            max_angle: Deg(45.0),
+        };
+        assert_eq!(deflection.angle(-0.9), Deg(-45.0));
+        assert_eq!(deflection.angle(0.1), Deg(0.0));
+        assert_eq!(deflection.angle(1.0), Deg(45.0));
+    }
+
+    #[test]
+    fn velocity_keeps_speed() {
+        let deflection = Deflection::default();
+        let velocity = deflection.velocity(0.7, 5.0, -1.0);
+        assert!((velocity.magnitude() - 5.0).abs() < 1e-5);
+        assert!(velocity.x < 0.0);
+        assert!(velocity.y > 0.0);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::ai::CpuPlayer;
+use crate::replay::{Recorder, ReplayEvent, ReplayPlayer};
+use crate::scene::{Action, Scene};
+use crate::score::Side;
+use crate::settings::Settings;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GamePhase {
+    /// Browsing the main menu or the settings
+    Menu,
+    Start,
+    Playing,
+    /// Game frozen with the pause menu open
+    Paused,
+    End,
+}
+
+/// A game of pong without its window: the scene, the phase of the match and
+/// where the paddles get their orders from.
+pub struct Game {
+    pub scene: Scene,
+    pub phase: GamePhase,
+    pub settings: Settings,
+    cpu_players: Vec<CpuPlayer>,
+    /// Simulation steps run since the game started, which time stamps recorded events
+    tick: u64,
+    recorder: Option<Recorder>,
+    /// Set when playing a recorded game back instead of taking orders from players
+    replay: Option<ReplayPlayer>,
+}
+
+impl Game {
+    pub fn new(seed: u64, settings: Settings, phase: GamePhase) -> Self {
+        let mut scene = Scene::new(seed);
+        settings.apply(&mut scene);
+
+        Self {
+            scene,
+            phase,
+            settings,
+            cpu_players: settings.cpu_player(seed).into_iter().collect(),
+            tick: 0,
+            recorder: None,
+            replay: None,
+        }
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Records the actions of the players from now on.
+    pub fn record(&mut self, recorder: Recorder) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Plays the events of a replay back as the simulation reaches them.
+    pub fn replay(&mut self, replay: ReplayPlayer) {
+        // The moves of the computer players were recorded along with the others
+        self.cpu_players.clear();
+        self.replay = Some(replay);
+    }
+
+    pub fn is_replay(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Whether a replay was played back to its end.
+    pub fn is_replay_finished(&self) -> bool {
+        self.replay.as_ref().is_some_and(ReplayPlayer::is_finished)
+    }
+
+    /// Has the computer play `side`, on top of the opponent chosen in the settings.
+    pub fn add_cpu_player(&mut self, cpu_player: CpuPlayer) {
+        self.cpu_players.push(cpu_player);
+    }
+
+    pub fn is_cpu_playing(&self, side: Side) -> bool {
+        self.cpu_players
+            .iter()
+            .any(|cpu_player| cpu_player.side() == side)
+    }
+
+    /// Applies an action coming from a player, recording it when a recording is running.
+    pub fn handle_scene_action(&mut self, action: Action) {
+        self.record_event(ReplayEvent::Action(action));
+        self.scene.handle_action(action);
+    }
+
+    /// Moves the game to `phase`, the menus and the input follow it.
+    pub fn set_phase(&mut self, phase: GamePhase) {
+        if phase != self.phase {
+            log::debug!("Phase {:?} -> {:?}", self.phase, phase);
+            self.phase = phase;
+        }
+    }
+
+    /// Serves the first ball of a match.
+    pub fn kickoff(&mut self) {
+        self.set_phase(GamePhase::Playing);
+        self.handle_scene_action(Action::Kickoff);
+    }
+
+    /// Clears the score, ready for a new match.
+    pub fn restart(&mut self) {
+        self.set_phase(GamePhase::Start);
+        self.handle_scene_action(Action::ResetGame);
+    }
+
+    pub fn apply_settings(&mut self, settings: Settings) {
+        self.settings = settings;
+        self.settings.apply(&mut self.scene);
+        // The opponent may have changed, other computer players stay
+        self.cpu_players
+            .retain(|cpu_player| cpu_player.side() != Side::Right);
+        self.cpu_players
+            .extend(self.settings.cpu_player(self.scene.seed()));
+        self.record_event(ReplayEvent::Settings(self.settings));
+    }
+
+    /// Runs one simulation step of `delta_time` seconds.
+    pub fn simulate(&mut self, delta_time: f32) {
+        let scene = &self.scene;
+        let actions: Vec<Action> = self
+            .cpu_players
+            .iter_mut()
+            .filter_map(|cpu_player| cpu_player.update(scene, delta_time))
+            .collect();
+        for action in actions {
+            self.handle_scene_action(action);
+        }
+        self.scene.update(delta_time);
+        self.tick += 1;
+
+        if let Some(winner) = self.scene.winner() {
+            let score = self.scene.score();
+            log::info!("{:?} player wins {} - {}", winner, score.left, score.right);
+            self.scene.handle_action(Action::GameOver);
+            self.set_phase(GamePhase::End);
+        }
+    }
+
+    /// Applies the events of the replay that are due before the next simulation step.
+    pub fn play_replay_events(&mut self) {
+        let tick = self.tick;
+        while let Some(event) = self
+            .replay
+            .as_mut()
+            .and_then(|replay| replay.next_event(tick))
+        {
+            match event {
+                ReplayEvent::Action(action) => {
+                    // The phase changes that went along with the action when it was recorded
+                    match action {
+                        Action::Kickoff => self.set_phase(GamePhase::Playing),
+                        Action::ResetGame => self.set_phase(GamePhase::Start),
+                        _ => {}
+                    }
+                    self.scene.handle_action(action);
+                }
+                ReplayEvent::Settings(settings) => {
+                    self.settings = settings;
+                    self.settings.apply(&mut self.scene);
+                }
+                ReplayEvent::End(score) => {
+                    let replayed = self.scene.score();
+                    if replayed == score {
+                        log::info!("Replay finished {} - {}", score.left, score.right);
+                    } else {
+                        log::warn!(
+                            "Replay went differently from the recorded game: {} - {} instead of {} - {}",
+                            replayed.left, replayed.right, score.left, score.right
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Ends the recording with the score, so its replay can tell whether it went the same way.
+    pub fn stop_recording(&mut self) {
+        self.record_event(ReplayEvent::End(self.scene.score()));
+        self.recorder = None;
+    }
+
+    fn record_event(&mut self, event: ReplayEvent) {
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(error) = recorder.record(self.tick, &event) {
+                log::error!("Could not write the recording, it stops here: {}", error);
+                self.recorder = None;
+            }
+        }
+    }
+}
@@ -1,16 +1,19 @@
 pub mod constants;
 pub mod debug;
+pub mod error;
 pub mod fps_limiter;
 pub mod platforms;
 pub mod share;
 pub mod structures;
+pub mod text;
 pub mod tools;
 pub mod window;
 
-use cgmath::Deg;
 use cgmath::Matrix4;
 use constants::*;
+use error::{GraphicsError, VkResultExt};
 use structures::{QueueFamilyIndices, SurfaceStuff};
+use text::{Text, TextRenderer};
 
 use ash::version::DeviceV1_0;
 use ash::version::InstanceV1_0;
@@ -18,6 +21,8 @@ use ash::vk;
 
 use std::ptr;
 
+use crate::camera::Camera;
+use crate::config::{GraphicsConfig, WindowConfig};
 use crate::scene::Scene;
 
 use self::structures::UniformBufferObject;
@@ -69,6 +74,10 @@ pub struct GraphicsManager {
 
     model_buffers: Vec<ModelBuffers>,
 
+    text_renderer: TextRenderer,
+    text_pipeline_layout: vk::PipelineLayout,
+    text_pipeline: vk::Pipeline,
+
     command_pool: vk::CommandPool,
     command_buffers: Vec<vk::CommandBuffer>,
 
@@ -78,38 +87,63 @@ pub struct GraphicsManager {
     current_frame: usize,
 
     is_framebuffer_resized: bool,
+
+    validation: debug::ValidationInfo,
+    max_frames_in_flight: usize,
 }
 
 impl GraphicsManager {
-    pub fn new(event_loop: &winit::event_loop::EventLoop<()>, scene: &Scene) -> GraphicsManager {
-        let window = window::init_window(event_loop, WINDOW_TITLE, WINDOW_WIDTH, WINDOW_HEIGHT);
-
-        let entry = ash::Entry::new().unwrap();
+    pub fn new(
+        event_loop: &winit::event_loop::EventLoop<()>,
+        scene: &Scene,
+        window_config: &WindowConfig,
+        graphics_config: &GraphicsConfig,
+    ) -> Result<GraphicsManager, GraphicsError> {
+        let window = window::init_window(event_loop, WINDOW_TITLE, window_config)?;
+        let mut validation = debug::ValidationInfo {
+            is_enable: graphics_config.validation,
+            ..VALIDATION
+        };
+        let max_frames_in_flight = graphics_config.max_frames_in_flight;
+
+        let entry = ash::Entry::new()?;
+        if validation.is_enable
+            && !debug::check_validation_layer_support(&entry, &validation.required_validation_layers)?
+        {
+            // Only developers have the layers installed, the game runs fine without them
+            log::warn!(
+                "Validation layers requested, but {} is not available, going on without it",
+                validation.required_validation_layers.join(", ")
+            );
+            validation.is_enable = false;
+        }
         let instance = share::create_instance(
             &entry,
             WINDOW_TITLE,
-            VALIDATION.is_enable,
-            &VALIDATION.required_validation_layers,
-        );
+            validation.is_enable,
+            &validation.required_validation_layers,
+        )?;
         let surface_stuff =
-            share::create_surface(&entry, &instance, &window, WINDOW_WIDTH, WINDOW_HEIGHT);
+            share::create_surface(&entry, &instance, &window)?;
         let (debug_utils_loader, debug_merssager) =
-            debug::setup_debug_utils(VALIDATION.is_enable, &entry, &instance);
-        let physical_device =
-            share::pick_physical_device(&instance, &surface_stuff, &DEVICE_EXTENSIONS);
+            debug::setup_debug_utils(validation.is_enable, &entry, &instance)?;
+        let physical_device = share::pick_physical_device(
+            &instance,
+            &surface_stuff,
+            &DEVICE_EXTENSIONS,
+            graphics_config.gpu.as_ref(),
+        )?;
         let physical_device_memory_properties =
             unsafe { instance.get_physical_device_memory_properties(physical_device) };
         let (device, queue_family) = share::create_logical_device(
             &instance,
             physical_device,
-            &VALIDATION,
+            &validation,
             &DEVICE_EXTENSIONS,
             &surface_stuff,
-        );
-        let graphics_queue =
-            unsafe { device.get_device_queue(queue_family.graphics_family.unwrap(), 0) };
-        let present_queue =
-            unsafe { device.get_device_queue(queue_family.present_family.unwrap(), 0) };
+        )?;
+        let graphics_queue = unsafe { device.get_device_queue(queue_family.graphics()?, 0) };
+        let present_queue = unsafe { device.get_device_queue(queue_family.present()?, 0) };
         let swapchain_stuff = share::create_swapchain(
             &instance,
             &device,
@@ -117,27 +151,27 @@ impl GraphicsManager {
             &window,
             &surface_stuff,
             &queue_family,
-        );
+        )?;
         let swapchain_imageviews = share::create_image_views(
             &device,
             swapchain_stuff.swapchain_format,
             &swapchain_stuff.swapchain_images,
-        );
-        let render_pass = share::create_render_pass(&device, swapchain_stuff.swapchain_format);
-        let ubo_layout = share::create_descriptor_set_layout(&device);
+        )?;
+        let render_pass = share::create_render_pass(&device, swapchain_stuff.swapchain_format)?;
+        let ubo_layout = share::create_descriptor_set_layout(&device)?;
         let (graphics_pipeline, pipeline_layout) = share::create_graphics_pipeline(
             &device,
             render_pass,
             swapchain_stuff.swapchain_extent,
             ubo_layout,
-        );
+        )?;
         let swapchain_framebuffers = share::create_framebuffers(
             &device,
             render_pass,
             &swapchain_imageviews,
             swapchain_stuff.swapchain_extent,
-        );
-        let command_pool = share::create_command_pool(&device, &queue_family);
+        )?;
+        let command_pool = share::create_command_pool(&device, &queue_family)?;
 
         let model_data = scene.get_model_data();
         let model_buffers: Vec<ModelBuffers> = model_data
@@ -149,30 +183,30 @@ impl GraphicsManager {
                     command_pool,
                     graphics_queue,
                     &md.model_mesh.vertices,
-                );
+                )?;
                 let (index_buffer, index_buffer_memory) = share::create_index_buffer(
                     &device,
                     &physical_device_memory_properties,
                     command_pool,
                     graphics_queue,
                     &md.model_mesh.indices,
-                );
+                )?;
                 let (uniform_buffers, uniform_buffers_memory) = share::create_uniform_buffers(
                     &device,
                     &physical_device_memory_properties,
                     swapchain_stuff.swapchain_images.len(),
-                );
+                )?;
                 let descriptor_pool =
-                    share::create_descriptor_pool(&device, swapchain_stuff.swapchain_images.len());
+                    share::create_descriptor_pool(&device, swapchain_stuff.swapchain_images.len())?;
                 let descriptor_sets = share::create_descriptor_sets(
                     &device,
                     descriptor_pool,
                     ubo_layout,
                     &uniform_buffers,
                     swapchain_stuff.swapchain_images.len(),
-                );
+                )?;
 
-                ModelBuffers {
+                Ok(ModelBuffers {
                     vertex_buffer,
                     vertex_buffer_memory,
                     index_buffer,
@@ -187,9 +221,23 @@ impl GraphicsManager {
                     uniform_buffers_memory,
                     descriptor_pool,
                     descriptor_sets,
-                }
+                })
             })
-            .collect();
+            .collect::<Result<_, GraphicsError>>()?;
+
+        let text_renderer = TextRenderer::new(
+            &device,
+            &physical_device_memory_properties,
+            command_pool,
+            graphics_queue,
+            swapchain_stuff.swapchain_images.len(),
+        )?;
+        let (text_pipeline, text_pipeline_layout) = share::create_text_pipeline(
+            &device,
+            render_pass,
+            swapchain_stuff.swapchain_extent,
+            text_renderer.descriptor_set_layout,
+        )?;
 
         let command_buffers = share::create_command_buffers(
             &device,
@@ -200,10 +248,13 @@ impl GraphicsManager {
             swapchain_stuff.swapchain_extent,
             pipeline_layout,
             &model_buffers,
-        );
-        let sync_ojbects = share::create_sync_objects(&device, MAX_FRAMES_IN_FLIGHT);
+            text_pipeline,
+            text_pipeline_layout,
+            &text_renderer,
+        )?;
+        let sync_ojbects = share::create_sync_objects(&device, max_frames_in_flight)?;
 
-        GraphicsManager {
+        Ok(GraphicsManager {
             window,
 
             _entry: entry,
@@ -235,6 +286,10 @@ impl GraphicsManager {
 
             model_buffers,
 
+            text_renderer,
+            text_pipeline_layout,
+            text_pipeline,
+
             command_pool,
             command_buffers,
 
@@ -244,34 +299,81 @@ impl GraphicsManager {
             current_frame: 0,
 
             is_framebuffer_resized: false,
-        }
+
+            validation,
+            max_frames_in_flight,
+        })
     }
 
     pub fn window_request_redraw(&mut self) {
         self.window.request_redraw();
     }
 
-    pub fn device_wait_idle(&mut self) {
+    pub fn device_wait_idle(&mut self) -> Result<(), GraphicsError> {
         unsafe {
             self.device
                 .device_wait_idle()
-                .expect("Failed to wait device idle!")
-        };
+                .context("Failed to wait device idle")
+        }
+    }
+
+    /// Draws `content` on top of the next frame.
+    ///
+    /// `position` is the top left corner of the text in pixels from the top left
+    /// corner of the window, `scale` multiplies the 8x13 pixels of each glyph and
+    /// `color` is RGBA. Texts have to be queued again for every frame.
+    pub fn draw_text(&mut self, content: &str, position: [f32; 2], scale: f32, color: [f32; 4]) {
+        self.text_renderer.queue(Text {
+            content: content.to_string(),
+            position,
+            scale,
+            color,
+        });
     }
 
-    pub fn draw_frame(&mut self, transforms: Vec<Matrix4<f32>>) {
+    /// Size in pixels of `content` drawn with `scale`, to lay texts out before drawing them.
+    pub fn text_size(&self, content: &str, scale: f32) -> [f32; 2] {
+        text::text_size(content, scale)
+    }
+
+    /// Size of the drawable area of the window, in pixels.
+    pub fn screen_size(&self) -> [f32; 2] {
+        [
+            self.swapchain_extent.width as f32,
+            self.swapchain_extent.height as f32,
+        ]
+    }
+
+    /// The window changed size, the swapchain is made again for the next frame.
+    pub fn window_resized(&mut self) {
+        self.is_framebuffer_resized = true;
+    }
+
+    /// Draws the models moved by `transforms` as `camera` sees them, and the queued texts.
+    pub fn draw_frame(
+        &mut self,
+        transforms: Vec<Matrix4<f32>>,
+        camera: &Camera,
+    ) -> Result<(), GraphicsError> {
+        // A minimized window has nothing to draw on, and no swapchain can be made for it
+        let window_size = self.window.inner_size();
+        if window_size.width == 0 || window_size.height == 0 {
+            self.text_renderer.discard_queue();
+            return Ok(());
+        }
+
         let wait_fences = [self.in_flight_fences[self.current_frame]];
 
         unsafe {
             self.device
-                .wait_for_fences(&wait_fences, true, std::u64::MAX)
-                .expect("Failed to wait for Fence!");
+                .wait_for_fences(&wait_fences, true, u64::MAX)
+                .context("Failed to wait for Fence")?;
         }
 
         let (image_index, _is_sub_optimal) = unsafe {
             let result = self.swapchain_loader.acquire_next_image(
                 self.swapchain,
-                std::u64::MAX,
+                u64::MAX,
                 self.image_available_semaphores[self.current_frame],
                 vk::Fence::null(),
             );
@@ -279,15 +381,25 @@ impl GraphicsManager {
                 Ok(image_index) => image_index,
                 Err(vk_result) => match vk_result {
                     vk::Result::ERROR_OUT_OF_DATE_KHR => {
-                        self.recreate_swapchain();
-                        return;
+                        self.text_renderer.discard_queue();
+                        return self.recreate_swapchain();
+                    }
+                    result => {
+                        return Err(GraphicsError::Vulkan {
+                            context: "Failed to acquire Swap Chain Image",
+                            result,
+                        })
                     }
-                    _ => panic!("Failed to acquire Swap Chain Image!"),
                 },
             }
         };
 
-        self.update_uniform_buffer(image_index as usize, transforms);
+        self.update_uniform_buffer(image_index as usize, transforms, camera)?;
+        self.text_renderer.update_vertex_buffer(
+            &self.device,
+            image_index as usize,
+            self.swapchain_extent,
+        )?;
 
         let wait_semaphores = [self.image_available_semaphores[self.current_frame]];
         let wait_stages = [vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
@@ -308,7 +420,7 @@ impl GraphicsManager {
         unsafe {
             self.device
                 .reset_fences(&wait_fences)
-                .expect("Failed to reset Fence!");
+                .context("Failed to reset Fence")?;
 
             self.device
                 .queue_submit(
@@ -316,7 +428,7 @@ impl GraphicsManager {
                     &submit_infos,
                     self.in_flight_fences[self.current_frame],
                 )
-                .expect("Failed to execute queue submit.");
+                .context("Failed to execute queue submit")?;
         }
 
         let swapchains = [self.swapchain];
@@ -341,20 +453,35 @@ impl GraphicsManager {
             Ok(_) => self.is_framebuffer_resized,
             Err(vk_result) => match vk_result {
                 vk::Result::ERROR_OUT_OF_DATE_KHR | vk::Result::SUBOPTIMAL_KHR => true,
-                _ => panic!("Failed to execute queue present."),
+                result => {
+                    return Err(GraphicsError::Vulkan {
+                        context: "Failed to execute queue present",
+                        result,
+                    })
+                }
             },
         };
         if is_resized {
             self.is_framebuffer_resized = false;
-            self.recreate_swapchain();
+            self.recreate_swapchain()?;
         }
 
-        self.current_frame = (self.current_frame + 1) % MAX_FRAMES_IN_FLIGHT;
+        self.current_frame = (self.current_frame + 1) % self.max_frames_in_flight;
+        Ok(())
     }
 
-    fn update_uniform_buffer(&mut self, current_image: usize, transforms: Vec<Matrix4<f32>>) {
+    fn update_uniform_buffer(
+        &mut self,
+        current_image: usize,
+        transforms: Vec<Matrix4<f32>>,
+        camera: &Camera,
+    ) -> Result<(), GraphicsError> {
         for (i, buffers) in self.model_buffers.iter_mut().enumerate() {
-            buffers.uniform_transform.model = transforms[i];
+            buffers.uniform_transform = UniformBufferObject {
+                model: transforms[i],
+                view: camera.view,
+                proj: camera.proj,
+            };
             let ubos = [buffers.uniform_transform];
             let buffer_size = (std::mem::size_of::<UniformBufferObject>() * ubos.len()) as u64;
 
@@ -367,7 +494,7 @@ impl GraphicsManager {
                         buffer_size,
                         vk::MemoryMapFlags::empty(),
                     )
-                    .expect("Failed to Map Memory")
+                    .context("Failed to Map Memory")?
                     as *mut UniformBufferObject;
 
                 data_ptr.copy_from_nonoverlapping(ubos.as_ptr(), ubos.len());
@@ -376,23 +503,19 @@ impl GraphicsManager {
                     .unmap_memory(buffers.uniform_buffers_memory[current_image]);
             }
         }
+
+        Ok(())
     }
 
-    fn recreate_swapchain(&mut self) {
+    fn recreate_swapchain(&mut self) -> Result<(), GraphicsError> {
         // parameters -------------
         let surface_suff = SurfaceStuff {
             surface_loader: self.surface_loader.clone(),
             surface: self.surface,
-            screen_width: WINDOW_WIDTH,
-            screen_height: WINDOW_HEIGHT,
         };
         // ------------------------
 
-        unsafe {
-            self.device
-                .device_wait_idle()
-                .expect("Failed to wait device idle!")
-        };
+        self.device_wait_idle()?;
         self.cleanup_swapchain();
 
         let swapchain_stuff = share::create_swapchain(
@@ -402,45 +525,39 @@ impl GraphicsManager {
             &self.window,
             &surface_suff,
             &self.queue_family,
-        );
+        )?;
         self.swapchain_loader = swapchain_stuff.swapchain_loader;
         self.swapchain = swapchain_stuff.swapchain;
         self.swapchain_images = swapchain_stuff.swapchain_images;
         self.swapchain_format = swapchain_stuff.swapchain_format;
         self.swapchain_extent = swapchain_stuff.swapchain_extent;
 
-        // update camera aspect ratio
-        for buffers in self.model_buffers.iter_mut() {
-            buffers.uniform_transform = UniformBufferObject {
-                model: buffers.uniform_transform.model,
-                view: buffers.uniform_transform.view,
-                proj: cgmath::perspective(
-                    Deg(45.0),
-                    self.swapchain_extent.width as f32 / self.swapchain_extent.height as f32,
-                    0.1,
-                    10.0,
-                ),
-            }
-        }
-
         self.swapchain_imageviews =
-            share::create_image_views(&self.device, self.swapchain_format, &self.swapchain_images);
-        self.render_pass = share::create_render_pass(&self.device, self.swapchain_format);
+            share::create_image_views(&self.device, self.swapchain_format, &self.swapchain_images)?;
+        self.render_pass = share::create_render_pass(&self.device, self.swapchain_format)?;
         let (graphics_pipeline, pipeline_layout) = share::create_graphics_pipeline(
             &self.device,
             self.render_pass,
             swapchain_stuff.swapchain_extent,
             self.ubo_layout,
-        );
+        )?;
         self.graphics_pipeline = graphics_pipeline;
         self.pipeline_layout = pipeline_layout;
+        let (text_pipeline, text_pipeline_layout) = share::create_text_pipeline(
+            &self.device,
+            self.render_pass,
+            self.swapchain_extent,
+            self.text_renderer.descriptor_set_layout,
+        )?;
+        self.text_pipeline = text_pipeline;
+        self.text_pipeline_layout = text_pipeline_layout;
 
         self.swapchain_framebuffers = share::create_framebuffers(
             &self.device,
             self.render_pass,
             &self.swapchain_imageviews,
             self.swapchain_extent,
-        );
+        )?;
         self.command_buffers = share::create_command_buffers(
             &self.device,
             self.command_pool,
@@ -450,7 +567,12 @@ impl GraphicsManager {
             self.swapchain_extent,
             pipeline_layout,
             &self.model_buffers,
-        );
+            self.text_pipeline,
+            self.text_pipeline_layout,
+            &self.text_renderer,
+        )?;
+
+        Ok(())
     }
 
     fn cleanup_swapchain(&self) {
@@ -460,6 +582,9 @@ impl GraphicsManager {
             for &framebuffer in self.swapchain_framebuffers.iter() {
                 self.device.destroy_framebuffer(framebuffer, None);
             }
+            self.device.destroy_pipeline(self.text_pipeline, None);
+            self.device
+                .destroy_pipeline_layout(self.text_pipeline_layout, None);
             self.device.destroy_pipeline(self.graphics_pipeline, None);
             self.device
                 .destroy_pipeline_layout(self.pipeline_layout, None);
@@ -476,7 +601,7 @@ impl GraphicsManager {
 impl Drop for GraphicsManager {
     fn drop(&mut self) {
         unsafe {
-            for i in 0..MAX_FRAMES_IN_FLIGHT {
+            for i in 0..self.max_frames_in_flight {
                 self.device
                     .destroy_semaphore(self.image_available_semaphores[i], None);
                 self.device
@@ -503,6 +628,8 @@ impl Drop for GraphicsManager {
                 self.device.free_memory(buffers.vertex_buffer_memory, None);
             }
 
+            self.text_renderer.destroy(&self.device);
+
             self.device
                 .destroy_descriptor_set_layout(self.ubo_layout, None);
 
@@ -511,7 +638,7 @@ impl Drop for GraphicsManager {
             self.device.destroy_device(None);
             self.surface_loader.destroy_surface(self.surface, None);
 
-            if VALIDATION.is_enable {
+            if self.validation.is_enable {
                 self.debug_utils_loader
                     .destroy_debug_utils_messenger(self.debug_merssager, None);
             }
@@ -519,3 +646,60 @@ impl Drop for GraphicsManager {
         }
     }
 }
+
+/// Prints the graphics cards Vulkan finds, whether the game can draw with them and
+/// which one it would pick with `graphics_config`.
+pub fn print_physical_devices(
+    event_loop: &winit::event_loop::EventLoop<()>,
+    graphics_config: &GraphicsConfig,
+) -> Result<(), GraphicsError> {
+    // The devices are checked against a surface, like when the game starts
+    let window = window::init_hidden_window(event_loop, WINDOW_TITLE)?;
+    let entry = ash::Entry::new()?;
+    let instance = share::create_instance(&entry, WINDOW_TITLE, false, &[])?;
+    let surface_stuff = share::create_surface(&entry, &instance, &window)?;
+
+    let devices =
+        share::enumerate_physical_devices(&instance, &surface_stuff, &DEVICE_EXTENSIONS);
+    let devices = match devices {
+        Ok(devices) => devices,
+        Err(error) => {
+            unsafe { destroy_instance(&instance, &surface_stuff) };
+            return Err(error);
+        }
+    };
+    let picked = share::choose_physical_device(&devices, graphics_config.gpu.as_ref());
+    if devices.is_empty() {
+        println!("No Vulkan device found");
+    }
+    for (index, device) in devices.iter().enumerate() {
+        let device_type = match device.device_type {
+            vk::PhysicalDeviceType::DISCRETE_GPU => "discrete GPU",
+            vk::PhysicalDeviceType::INTEGRATED_GPU => "integrated GPU",
+            vk::PhysicalDeviceType::VIRTUAL_GPU => "virtual GPU",
+            vk::PhysicalDeviceType::CPU => "CPU",
+            _ => "other",
+        };
+        let status = if picked == Some(index) {
+            ", picked"
+        } else if !device.is_suitable {
+            ", not suitable"
+        } else {
+            ""
+        };
+        println!("{}: {} ({}{})", index, device.name, device_type, status);
+    }
+    if let (None, Some(gpu)) = (picked, &graphics_config.gpu) {
+        println!("No suitable device matches {}", gpu);
+    }
+
+    unsafe { destroy_instance(&instance, &surface_stuff) };
+    Ok(())
+}
+
+unsafe fn destroy_instance(instance: &ash::Instance, surface_stuff: &SurfaceStuff) {
+    surface_stuff
+        .surface_loader
+        .destroy_surface(surface_stuff.surface, None);
+    instance.destroy_instance(None);
+}
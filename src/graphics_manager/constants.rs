@@ -9,8 +9,7 @@ pub const ENGINE_VERSION: u32 = vk_make_version!(1, 0, 0);
 pub const API_VERSION: u32 = vk_make_version!(1, 0, 92);
 
 pub const WINDOW_TITLE: &str = "Pong-rust";
-pub const WINDOW_WIDTH: u32 = 800;
-pub const WINDOW_HEIGHT: u32 = 600;
+/// Validation layers used when validation is turned on in the configuration
 pub const VALIDATION: ValidationInfo = ValidationInfo {
     is_enable: true,
     required_validation_layers: ["VK_LAYER_KHRONOS_validation"],
@@ -18,8 +17,6 @@ pub const VALIDATION: ValidationInfo = ValidationInfo {
 pub const DEVICE_EXTENSIONS: DeviceExtension = DeviceExtension {
     names: ["VK_KHR_swapchain"],
 };
-pub const MAX_FRAMES_IN_FLIGHT: usize = 2;
-pub const IS_PAINT_FPS_COUNTER: bool = true;
 
 impl DeviceExtension {
     pub fn get_extensions_raw_names(&self) -> [*const c_char; 1] {
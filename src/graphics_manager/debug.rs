@@ -5,31 +5,57 @@ use std::ffi::CStr;
 use std::os::raw::c_void;
 use std::ptr;
 
+use super::error::{GraphicsError, VkResultExt};
+
 unsafe extern "system" fn vulkan_debug_utils_callback(
     message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
     message_type: vk::DebugUtilsMessageTypeFlagsEXT,
     p_callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT,
     _p_user_data: *mut c_void,
 ) -> vk::Bool32 {
-    let severity = match message_severity {
-        vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE => "[Verbose]",
-        vk::DebugUtilsMessageSeverityFlagsEXT::WARNING => "[Warning]",
-        vk::DebugUtilsMessageSeverityFlagsEXT::ERROR => "[Error]",
-        vk::DebugUtilsMessageSeverityFlagsEXT::INFO => "[Info]",
-        _ => "[Unknown]",
-    };
-    let types = match message_type {
-        vk::DebugUtilsMessageTypeFlagsEXT::GENERAL => "[General]",
-        vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE => "[Performance]",
-        vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION => "[Validation]",
-        _ => "[Unknown]",
+    let target = match message_type {
+        vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE => "vulkan::performance",
+        vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION => "vulkan::validation",
+        _ => "vulkan::general",
     };
     let message = CStr::from_ptr((*p_callback_data).p_message);
-    println!("[Debug]{}{}{:?}", severity, types, message);
+    log::log!(
+        target: target,
+        severity_level(message_severity),
+        "{}",
+        message.to_string_lossy()
+    );
 
     vk::FALSE
 }
 
+/// Level the messages of the validation layers are logged at.
+fn severity_level(severity: vk::DebugUtilsMessageSeverityFlagsEXT) -> log::Level {
+    match severity {
+        vk::DebugUtilsMessageSeverityFlagsEXT::ERROR => log::Level::Error,
+        vk::DebugUtilsMessageSeverityFlagsEXT::WARNING => log::Level::Warn,
+        vk::DebugUtilsMessageSeverityFlagsEXT::INFO => log::Level::Debug,
+        _ => log::Level::Trace,
+    }
+}
+
+/// Severities worth asking the layers for, those the logger would drop only slow the game down.
+///
+/// Errors are always asked for, Vulkan wants at least one severity.
+fn logged_severities() -> vk::DebugUtilsMessageSeverityFlagsEXT {
+    [
+        vk::DebugUtilsMessageSeverityFlagsEXT::WARNING,
+        vk::DebugUtilsMessageSeverityFlagsEXT::INFO,
+        vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE,
+    ]
+    .iter()
+    .filter(|severity| severity_level(**severity) <= log::max_level())
+    .fold(
+        vk::DebugUtilsMessageSeverityFlagsEXT::ERROR,
+        |all, severity| all | *severity,
+    )
+}
+
 pub struct ValidationInfo {
     pub is_enable: bool,
     pub required_validation_layers: [&'static str; 1],
@@ -38,14 +64,13 @@ pub struct ValidationInfo {
 pub fn check_validation_layer_support(
     entry: &ash::Entry,
     required_validation_layers: &[&str],
-) -> bool {
+) -> Result<bool, GraphicsError> {
     let layer_properties = entry
         .enumerate_instance_layer_properties()
-        .expect("Failed to enumerate Instance Layers Properties");
+        .context("Failed to enumerate Instance Layers Properties")?;
 
     if layer_properties.is_empty() {
-        eprintln!("No available layers.");
-        return false;
+        return Ok(false);
     }
 
     for required_layer_name in required_validation_layers.iter() {
@@ -60,32 +85,32 @@ pub fn check_validation_layer_support(
         }
 
         if !is_layer_found {
-            return false;
+            return Ok(false);
         }
     }
 
-    true
+    Ok(true)
 }
 
 pub fn setup_debug_utils(
     is_enable_debug: bool,
     entry: &ash::Entry,
     instance: &ash::Instance,
-) -> (ash::extensions::ext::DebugUtils, vk::DebugUtilsMessengerEXT) {
+) -> Result<(ash::extensions::ext::DebugUtils, vk::DebugUtilsMessengerEXT), GraphicsError> {
     let debug_utils_loader = ash::extensions::ext::DebugUtils::new(entry, instance);
 
     if !is_enable_debug {
-        (debug_utils_loader, ash::vk::DebugUtilsMessengerEXT::null())
+        Ok((debug_utils_loader, ash::vk::DebugUtilsMessengerEXT::null()))
     } else {
         let messenger_ci = populate_debug_messenger_create_info();
 
         let utils_messenger = unsafe {
             debug_utils_loader
                 .create_debug_utils_messenger(&messenger_ci, None)
-                .expect("Debug Utils Callback")
+                .context("Failed to set up the Debug Utils Callback")?
         };
 
-        (debug_utils_loader, utils_messenger)
+        Ok((debug_utils_loader, utils_messenger))
     }
 }
 
@@ -94,10 +119,7 @@ pub fn populate_debug_messenger_create_info() -> vk::DebugUtilsMessengerCreateIn
         s_type: vk::StructureType::DEBUG_UTILS_MESSENGER_CREATE_INFO_EXT,
         p_next: ptr::null(),
         flags: vk::DebugUtilsMessengerCreateFlagsEXT::empty(),
-        message_severity: vk::DebugUtilsMessageSeverityFlagsEXT::WARNING |
-            // vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE |
-            // vk::DebugUtilsMessageSeverityFlagsEXT::INFO |
-            vk::DebugUtilsMessageSeverityFlagsEXT::ERROR,
+        message_severity: logged_severities(),
         message_type: vk::DebugUtilsMessageTypeFlagsEXT::GENERAL
             | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE
             | vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION,
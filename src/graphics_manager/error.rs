@@ -0,0 +1,83 @@
+use ash::vk;
+
+use std::fmt;
+
+use crate::config::DeviceChoice;
+
+/// Why the renderer could not start, or could not draw a frame.
+#[derive(Debug)]
+pub enum GraphicsError {
+    /// The Vulkan library, or a function it should provide, could not be loaded
+    Loading(String),
+    /// The window could not be created, or is of a kind the renderer can't draw to
+    Window(String),
+    /// A Vulkan call failed, `context` says which one
+    Vulkan {
+        context: &'static str,
+        result: vk::Result,
+    },
+    /// None of the graphics cards can draw the game, or none of those that can is the `gpu` chosen
+    NoSuitableGpu {
+        gpu: Option<DeviceChoice>,
+    },
+    NoSuitableMemoryType,
+    /// Something the renderer relies on is not as it should be, which is a bug rather
+    /// than a problem with the graphics card
+    Internal(&'static str),
+}
+
+impl fmt::Display for GraphicsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GraphicsError::Loading(reason) => write!(f, "could not load Vulkan: {}", reason),
+            GraphicsError::Window(reason) => write!(f, "window {}", reason),
+            GraphicsError::Vulkan { context, result } => {
+                write!(f, "{}: {} ({:?})", context, result, result)
+            }
+            GraphicsError::NoSuitableGpu { gpu: None } => write!(
+                f,
+                "no graphics card with the Vulkan features the game needs, see --list-devices"
+            ),
+            GraphicsError::NoSuitableGpu { gpu: Some(gpu) } => write!(
+                f,
+                "no graphics card that can draw the game matches {}, see --list-devices",
+                gpu
+            ),
+            GraphicsError::NoSuitableMemoryType => {
+                write!(f, "no memory type of the graphics card fits the game")
+            }
+            GraphicsError::Internal(reason) => write!(f, "internal renderer error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for GraphicsError {}
+
+impl From<ash::LoadingError> for GraphicsError {
+    fn from(error: ash::LoadingError) -> Self {
+        GraphicsError::Loading(error.to_string())
+    }
+}
+
+impl From<ash::InstanceError> for GraphicsError {
+    fn from(error: ash::InstanceError) -> Self {
+        match error {
+            ash::InstanceError::LoadError(names) => GraphicsError::Loading(names.join(", ")),
+            ash::InstanceError::VkError(result) => GraphicsError::Vulkan {
+                context: "Failed to create instance",
+                result,
+            },
+        }
+    }
+}
+
+/// Turns the error of a Vulkan call into a `GraphicsError` saying what failed.
+pub trait VkResultExt<T> {
+    fn context(self, context: &'static str) -> Result<T, GraphicsError>;
+}
+
+impl<T> VkResultExt<T> for Result<T, vk::Result> {
+    fn context(self, context: &'static str) -> Result<T, GraphicsError> {
+        self.map_err(|result| GraphicsError::Vulkan { context, result })
+    }
+}
@@ -1,48 +1,169 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-const SAMPLE_COUNT: usize = 5;
-const SAMPLE_COUNT_FLOAT: f32 = SAMPLE_COUNT as f32;
+/// Frames kept to compute the frame time statistics.
+const SAMPLE_COUNT: usize = 120;
+
+/// The OS may oversleep by about this much, so the end of a frame is waited for
+/// by spinning instead.
+const SPIN_MARGIN: Duration = Duration::from_millis(2);
+
+/// Frame times over the last frames, in milliseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    pub min: f32,
+    pub avg: f32,
+    pub max: f32,
+    /// 99% of the frames took at most this long
+    pub p99: f32,
+}
+
+impl FrameStats {
+    /// Statistics of `samples`, frame times in seconds.
+    fn from_samples(samples: &[f32]) -> FrameStats {
+        if samples.is_empty() {
+            return FrameStats::default();
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let p99_index = ((sorted.len() as f32 * 0.99).ceil() as usize).max(1) - 1;
+
+        FrameStats {
+            min: sorted[0] * 1000.0,
+            avg: sorted.iter().sum::<f32>() / sorted.len() as f32 * 1000.0,
+            max: sorted[sorted.len() - 1] * 1000.0,
+            p99: sorted[p99_index] * 1000.0,
+        }
+    }
+}
 
 pub struct FPSLimiter {
-    counter: Instant,
-    samples: [u32; SAMPLE_COUNT],
+    /// Shortest time a frame may take, `None` when the frame rate is unlimited
+    target_frame_time: Option<Duration>,
+    frame_start: Instant,
+    delta_frame: Duration,
+    /// Durations of the last frames in seconds, used as a ring buffer
+    samples: Vec<f32>,
     current_frame: usize,
-    delta_frame: u32,
 }
 
 impl FPSLimiter {
-    pub fn new() -> FPSLimiter {
-        FPSLimiter {
-            counter: Instant::now(),
-            samples: [0; SAMPLE_COUNT],
+    /// Limiter holding the game to `target_fps` frames per second, or not limiting it when `None`.
+    pub fn new(target_fps: Option<u32>) -> FPSLimiter {
+        let mut limiter = FPSLimiter {
+            target_frame_time: None,
+            frame_start: Instant::now(),
+            delta_frame: Duration::from_secs(0),
+            samples: Vec::with_capacity(SAMPLE_COUNT),
             current_frame: 0,
-            delta_frame: 0,
-        }
+        };
+        limiter.set_target_fps(target_fps);
+        limiter
+    }
+
+    pub fn set_target_fps(&mut self, target_fps: Option<u32>) {
+        self.target_frame_time = target_fps
+            .filter(|fps| *fps > 0)
+            .map(|fps| Duration::from_secs(1) / fps);
     }
 
-    /// Call this function in game loop to update its inner status.
+    /// Call this function at the end of each frame of the game loop. It waits
+    /// until the frame lasted as long as the target frame time, then starts the next one.
     pub fn tick_frame(&mut self) {
-        let time_elapsed = self.counter.elapsed();
-        self.counter = Instant::now();
+        if let Some(target_frame_time) = self.target_frame_time {
+            let deadline = self.frame_start + target_frame_time;
+            let now = Instant::now();
+            if deadline > now + SPIN_MARGIN {
+                std::thread::sleep(deadline - now - SPIN_MARGIN);
+            }
+            while Instant::now() < deadline {
+                std::hint::spin_loop();
+            }
+        }
+
+        let now = Instant::now();
+        self.delta_frame = now - self.frame_start;
+        self.frame_start = now;
 
-        self.delta_frame = time_elapsed.subsec_micros();
-        self.samples[self.current_frame] = self.delta_frame;
+        let sample = self.delta_frame.as_secs_f32();
+        if self.samples.len() < SAMPLE_COUNT {
+            self.samples.push(sample);
+        } else {
+            self.samples[self.current_frame] = sample;
+        }
         self.current_frame = (self.current_frame + 1) % SAMPLE_COUNT;
     }
 
-    /// Calculate the current FPS.
+    /// Forget the time elapsed since the last frame, so the next delta time
+    /// doesn't include a stretch where the game was not running, like a pause.
+    pub fn reset(&mut self) {
+        self.frame_start = Instant::now();
+        self.delta_frame = Duration::from_secs(0);
+    }
+
+    /// Calculate the current FPS, from the average frame time.
     pub fn fps(&self) -> f32 {
-        let mut sum = 0_u32;
-        self.samples.iter().for_each(|val| {
-            sum += val;
-        });
+        let stats = self.stats();
+        if stats.avg > 0.0 {
+            1000.0 / stats.avg
+        } else {
+            0.0
+        }
+    }
 
-        1_000_000.0_f32 / (sum as f32 / SAMPLE_COUNT_FLOAT)
+    pub fn stats(&self) -> FrameStats {
+        FrameStats::from_samples(&self.samples)
     }
 
-    /// Return current delta time in seconds
-    /// this function ignore its second part, since the second is mostly zero.
+    /// Return the duration of the last whole frame in seconds, waiting included.
     pub fn delta_time(&self) -> f32 {
-        self.delta_frame as f32 / 1_000_000.0_f32 // time in second
+        self.delta_frame.as_secs_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_cover_the_samples() {
+        let samples: Vec<f32> = (1..=100).map(|ms| ms as f32 / 1000.0).collect();
+        let stats = FrameStats::from_samples(&samples);
+
+        assert!((stats.min - 1.0).abs() < 1e-3);
+        assert!((stats.avg - 50.5).abs() < 1e-3);
+        assert!((stats.max - 100.0).abs() < 1e-3);
+        assert!((stats.p99 - 99.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn frames_last_at_least_the_target_time() {
+        let mut limiter = FPSLimiter::new(Some(200));
+        limiter.tick_frame();
+        limiter.tick_frame();
+
+        assert!(limiter.delta_time() >= 0.005);
+        assert!(limiter.stats().max >= 5.0);
+    }
+
+    #[test]
+    fn unlimited_frames_are_not_held_back() {
+        let mut limiter = FPSLimiter::new(None);
+        limiter.tick_frame();
+
+        assert!(limiter.delta_time() < 0.005);
+    }
+
+    #[test]
+    fn reset_leaves_a_gap_out_of_the_next_frame() {
+        let mut limiter = FPSLimiter::new(None);
+        limiter.tick_frame();
+        // Like a pause: no frame for a while, then the game resumes
+        std::thread::sleep(Duration::from_millis(50));
+        limiter.reset();
+        assert_eq!(limiter.delta_time(), 0.0);
+
+        limiter.tick_frame();
+        assert!(limiter.delta_time() < 0.025);
     }
 }
@@ -11,6 +11,8 @@ use ash::extensions::mvk::MacOSSurface;
 use ash::extensions::ext::DebugUtils;
 use ash::extensions::khr::Surface;
 
+use super::error::{GraphicsError, VkResultExt};
+
 #[cfg(target_os = "macos")]
 use cocoa::appkit::{NSView, NSWindow};
 #[cfg(target_os = "macos")]
@@ -55,12 +57,19 @@ pub unsafe fn create_surface<E: EntryV1_0, I: InstanceV1_0>(
     entry: &E,
     instance: &I,
     window: &winit::window::Window,
-) -> Result<vk::SurfaceKHR, vk::Result> {
+) -> Result<vk::SurfaceKHR, GraphicsError> {
     use std::ptr;
     use winit::platform::unix::WindowExtUnix;
 
-    let x11_display = window.xlib_display().unwrap();
-    let x11_window = window.xlib_window().unwrap();
+    // Winit opens the window on Wayland when it can, and only X11 surfaces are made here
+    let (x11_display, x11_window) = match (window.xlib_display(), window.xlib_window()) {
+        (Some(display), Some(window)) => (display, window),
+        _ => {
+            return Err(GraphicsError::Window(
+                "is not an X11 window, run the game with WINIT_UNIX_BACKEND=x11".to_string(),
+            ))
+        }
+    };
     let x11_create_info = vk::XlibSurfaceCreateInfoKHR {
         s_type: vk::StructureType::XLIB_SURFACE_CREATE_INFO_KHR,
         p_next: ptr::null(),
@@ -69,7 +78,9 @@ pub unsafe fn create_surface<E: EntryV1_0, I: InstanceV1_0>(
         dpy: x11_display as *mut vk::Display,
     };
     let xlib_surface_loader = XlibSurface::new(entry, instance);
-    xlib_surface_loader.create_xlib_surface(&x11_create_info, None)
+    xlib_surface_loader
+        .create_xlib_surface(&x11_create_info, None)
+        .context("Failed to create surface")
 }
 
 #[cfg(target_os = "macos")]
@@ -77,7 +88,7 @@ pub unsafe fn create_surface<E: EntryV1_0, I: InstanceV1_0>(
     entry: &E,
     instance: &I,
     window: &winit::window::Window,
-) -> Result<vk::SurfaceKHR, vk::Result> {
+) -> Result<vk::SurfaceKHR, GraphicsError> {
     use std::mem;
     use std::os::raw::c_void;
     use std::ptr;
@@ -105,7 +116,9 @@ pub unsafe fn create_surface<E: EntryV1_0, I: InstanceV1_0>(
     };
 
     let macos_surface_loader = MacOSSurface::new(entry, instance);
-    macos_surface_loader.create_mac_os_surface_mvk(&create_info, None)
+    macos_surface_loader
+        .create_mac_os_surface_mvk(&create_info, None)
+        .context("Failed to create surface")
 }
 
 #[cfg(target_os = "windows")]
@@ -113,7 +126,7 @@ pub unsafe fn create_surface<E: EntryV1_0, I: InstanceV1_0>(
     entry: &E,
     instance: &I,
     window: &winit::window::Window,
-) -> Result<vk::SurfaceKHR, vk::Result> {
+) -> Result<vk::SurfaceKHR, GraphicsError> {
     use std::os::raw::c_void;
     use std::ptr;
     use winapi::shared::windef::HWND;
@@ -130,6 +143,8 @@ pub unsafe fn create_surface<E: EntryV1_0, I: InstanceV1_0>(
         hwnd: hwnd as *const c_void,
     };
     let win32_surface_loader = Win32Surface::new(entry, instance);
-    win32_surface_loader.create_win32_surface(&win32_create_info, None)
+    win32_surface_loader
+        .create_win32_surface(&win32_create_info, None)
+        .context("Failed to create surface")
 }
 // ------------------------------------------------------------------------
@@ -10,9 +10,13 @@ use std::ptr;
 
 use crate::graphics_manager::constants::*;
 use crate::graphics_manager::debug;
+use crate::graphics_manager::error::{GraphicsError, VkResultExt};
 use crate::graphics_manager::platforms;
 use crate::graphics_manager::structures::*;
+use crate::config::DeviceChoice;
+use crate::mesh::Vertex;
 
+use super::text::TextRenderer;
 use super::ModelBuffers;
 
 pub fn create_instance(
@@ -20,14 +24,11 @@ pub fn create_instance(
     window_title: &str,
     is_enable_debug: bool,
     required_validation_layers: &[&str],
-) -> ash::Instance {
-    if is_enable_debug && !debug::check_validation_layer_support(entry, required_validation_layers)
-    {
-        panic!("Validation layers requested, but not available!");
-    }
-
-    let app_name = CString::new(window_title).unwrap();
-    let engine_name = CString::new("Vulkan Engine").unwrap();
+) -> Result<ash::Instance, GraphicsError> {
+    let app_name = CString::new(window_title)
+        .map_err(|_| GraphicsError::Internal("the window title holds a nul byte"))?;
+    let engine_name = CString::new("Vulkan Engine")
+        .map_err(|_| GraphicsError::Internal("the engine name holds a nul byte"))?;
     let app_info = vk::ApplicationInfo {
         p_application_name: app_name.as_ptr(),
         s_type: vk::StructureType::APPLICATION_INFO,
@@ -44,10 +45,7 @@ pub fn create_instance(
     // VK_EXT debug report has been requested here.
     let extension_names = platforms::required_extension_names();
 
-    let requred_validation_layer_raw_names: Vec<CString> = required_validation_layers
-        .iter()
-        .map(|layer_name| CString::new(*layer_name).unwrap())
-        .collect();
+    let requred_validation_layer_raw_names = layer_raw_names(required_validation_layers)?;
     let layer_names: Vec<*const i8> = requred_validation_layer_raw_names
         .iter()
         .map(|layer_name| layer_name.as_ptr())
@@ -55,7 +53,7 @@ pub fn create_instance(
 
     let create_info = vk::InstanceCreateInfo {
         s_type: vk::StructureType::INSTANCE_CREATE_INFO,
-        p_next: if VALIDATION.is_enable {
+        p_next: if is_enable_debug {
             &debug_utils_create_info as *const vk::DebugUtilsMessengerCreateInfoEXT as *const c_void
         } else {
             ptr::null()
@@ -76,58 +74,97 @@ pub fn create_instance(
         enabled_extension_count: extension_names.len() as u32,
     };
 
-    let instance: ash::Instance = unsafe {
-        entry
-            .create_instance(&create_info, None)
-            .expect("Failed to create instance!")
-    };
+    let instance: ash::Instance = unsafe { entry.create_instance(&create_info, None)? };
 
-    instance
+    Ok(instance)
+}
+
+/// Validation layer names as the C strings Vulkan takes.
+fn layer_raw_names(layer_names: &[&str]) -> Result<Vec<CString>, GraphicsError> {
+    layer_names
+        .iter()
+        .map(|layer_name| CString::new(*layer_name))
+        .collect::<Result<_, _>>()
+        .map_err(|_| GraphicsError::Internal("a validation layer name holds a nul byte"))
 }
 
 pub fn create_surface(
     entry: &ash::Entry,
     instance: &ash::Instance,
     window: &winit::window::Window,
-    screen_width: u32,
-    screen_height: u32,
-) -> SurfaceStuff {
+) -> Result<SurfaceStuff, GraphicsError> {
     let surface = unsafe {
-        platforms::create_surface(entry, instance, window).expect("Failed to create surface.")
+        platforms::create_surface(entry, instance, window)?
     };
     let surface_loader = ash::extensions::khr::Surface::new(entry, instance);
 
-    SurfaceStuff {
+    Ok(SurfaceStuff {
         surface_loader,
         surface,
-        screen_width,
-        screen_height,
-    }
+    })
 }
 
-pub fn pick_physical_device(
+/// A graphics card as `pick_physical_device` sees it.
+pub struct PhysicalDeviceInfo {
+    pub physical_device: vk::PhysicalDevice,
+    pub name: String,
+    pub device_type: vk::PhysicalDeviceType,
+    pub is_suitable: bool,
+}
+
+pub fn enumerate_physical_devices(
     instance: &ash::Instance,
     surface_stuff: &SurfaceStuff,
     required_device_extensions: &DeviceExtension,
-) -> vk::PhysicalDevice {
+) -> Result<Vec<PhysicalDeviceInfo>, GraphicsError> {
     let physical_devices = unsafe {
         instance
             .enumerate_physical_devices()
-            .expect("Failed to enumerate Physical Devices!")
+            .context("Failed to enumerate Physical Devices")?
     };
 
-    let result = physical_devices.iter().find(|physical_device| {
-        is_physical_device_suitable(
-            instance,
-            **physical_device,
-            surface_stuff,
-            required_device_extensions,
-        )
-    });
-
-    match result {
-        Some(p_physical_device) => *p_physical_device,
-        None => panic!("Failed to find a suitable GPU!"),
+    physical_devices
+        .iter()
+        .map(|physical_device| {
+            let properties = unsafe { instance.get_physical_device_properties(*physical_device) };
+            Ok(PhysicalDeviceInfo {
+                physical_device: *physical_device,
+                name: super::tools::vk_to_string(&properties.device_name),
+                device_type: properties.device_type,
+                is_suitable: is_physical_device_suitable(
+                    instance,
+                    *physical_device,
+                    surface_stuff,
+                    required_device_extensions,
+                )?,
+            })
+        })
+        .collect()
+}
+
+/// Index, in `devices`, of the first suitable device that is the `gpu` chosen.
+pub fn choose_physical_device(
+    devices: &[PhysicalDeviceInfo],
+    gpu: Option<&DeviceChoice>,
+) -> Option<usize> {
+    devices.iter().enumerate().position(|(index, device)| {
+        device.is_suitable && gpu.iter().all(|gpu| gpu.matches(index, &device.name))
+    })
+}
+
+pub fn pick_physical_device(
+    instance: &ash::Instance,
+    surface_stuff: &SurfaceStuff,
+    required_device_extensions: &DeviceExtension,
+    gpu: Option<&DeviceChoice>,
+) -> Result<vk::PhysicalDevice, GraphicsError> {
+    let devices = enumerate_physical_devices(instance, surface_stuff, required_device_extensions)?;
+
+    match choose_physical_device(&devices, gpu) {
+        Some(index) => Ok(devices[index].physical_device),
+        None => Err(GraphicsError::NoSuitableGpu {
+            gpu: gpu.cloned(),
+        }),
     }
 }
 
@@ -136,26 +173,26 @@ pub fn is_physical_device_suitable(
     physical_device: vk::PhysicalDevice,
     surface_stuff: &SurfaceStuff,
     required_device_extensions: &DeviceExtension,
-) -> bool {
+) -> Result<bool, GraphicsError> {
     let device_features = unsafe { instance.get_physical_device_features(physical_device) };
 
     let indices = find_queue_family(instance, physical_device, surface_stuff);
 
     let is_queue_family_supported = indices.is_complete();
     let is_device_extension_supported =
-        check_device_extension_support(instance, physical_device, required_device_extensions);
+        check_device_extension_support(instance, physical_device, required_device_extensions)?;
     let is_swapchain_supported = if is_device_extension_supported {
-        let swapchain_support = query_swapchain_support(physical_device, surface_stuff);
+        let swapchain_support = query_swapchain_support(physical_device, surface_stuff)?;
         !swapchain_support.formats.is_empty() && !swapchain_support.present_modes.is_empty()
     } else {
         false
     };
     let is_support_sampler_anisotropy = device_features.sampler_anisotropy == 1;
 
-    is_queue_family_supported
+    Ok(is_queue_family_supported
         && is_device_extension_supported
         && is_swapchain_supported
-        && is_support_sampler_anisotropy
+        && is_support_sampler_anisotropy)
 }
 
 pub fn create_logical_device(
@@ -164,14 +201,11 @@ pub fn create_logical_device(
     validation: &super::debug::ValidationInfo,
     device_extensions: &DeviceExtension,
     surface_stuff: &SurfaceStuff,
-) -> (ash::Device, QueueFamilyIndices) {
+) -> Result<(ash::Device, QueueFamilyIndices), GraphicsError> {
     let indices = find_queue_family(instance, physical_device, surface_stuff);
 
     use std::collections::HashSet;
-    let unique_queue_families = HashSet::from([
-        indices.graphics_family.unwrap(),
-        indices.present_family.unwrap(),
-    ]);
+    let unique_queue_families = HashSet::from([indices.graphics()?, indices.present()?]);
 
     let queue_priorities = [1.0_f32];
     let queue_create_infos: Vec<vk::DeviceQueueCreateInfo> = unique_queue_families
@@ -191,11 +225,8 @@ pub fn create_logical_device(
         ..Default::default()
     };
 
-    let required_validation_layer_raw_names: Vec<CString> = validation
-        .required_validation_layers
-        .iter()
-        .map(|layer_name| CString::new(*layer_name).unwrap())
-        .collect();
+    let required_validation_layer_raw_names =
+        layer_raw_names(&validation.required_validation_layers)?;
     let enable_layer_names: Vec<*const c_char> = required_validation_layer_raw_names
         .iter()
         .map(|layer_name| layer_name.as_ptr())
@@ -227,10 +258,10 @@ pub fn create_logical_device(
     let device: ash::Device = unsafe {
         instance
             .create_device(physical_device, &device_create_info, None)
-            .expect("Failed to create logical Device!")
+            .context("Failed to create logical Device")?
     };
 
-    (device, indices)
+    Ok((device, indices))
 }
 
 pub fn find_queue_family(
@@ -275,11 +306,11 @@ pub fn check_device_extension_support(
     instance: &ash::Instance,
     physical_device: vk::PhysicalDevice,
     device_extensions: &DeviceExtension,
-) -> bool {
+) -> Result<bool, GraphicsError> {
     let available_extensions = unsafe {
         instance
             .enumerate_device_extension_properties(physical_device)
-            .expect("Failed to get device extension properties.")
+            .context("Failed to get device extension properties")?
     };
 
     let available_extension_names: Vec<String> = available_extensions
@@ -297,32 +328,32 @@ pub fn check_device_extension_support(
         required_extensions.remove(extension_name);
     }
 
-    required_extensions.is_empty()
+    Ok(required_extensions.is_empty())
 }
 
 pub fn query_swapchain_support(
     physical_device: vk::PhysicalDevice,
     surface_stuff: &SurfaceStuff,
-) -> SwapChainSupportDetail {
+) -> Result<SwapChainSupportDetail, GraphicsError> {
     unsafe {
         let capabilities = surface_stuff
             .surface_loader
             .get_physical_device_surface_capabilities(physical_device, surface_stuff.surface)
-            .expect("Failed to query for surface capabilities.");
+            .context("Failed to query for surface capabilities")?;
         let formats = surface_stuff
             .surface_loader
             .get_physical_device_surface_formats(physical_device, surface_stuff.surface)
-            .expect("Failed to query for surface formats.");
+            .context("Failed to query for surface formats")?;
         let present_modes = surface_stuff
             .surface_loader
             .get_physical_device_surface_present_modes(physical_device, surface_stuff.surface)
-            .expect("Failed to query for surface present mode.");
+            .context("Failed to query for surface present mode")?;
 
-        SwapChainSupportDetail {
+        Ok(SwapChainSupportDetail {
             capabilities,
             formats,
             present_modes,
-        }
+        })
     }
 }
 
@@ -333,10 +364,10 @@ pub fn create_swapchain(
     window: &winit::window::Window,
     surface_stuff: &SurfaceStuff,
     queue_family: &QueueFamilyIndices,
-) -> SwapChainStuff {
-    let swapchain_support = query_swapchain_support(physical_device, surface_stuff);
+) -> Result<SwapChainStuff, GraphicsError> {
+    let swapchain_support = query_swapchain_support(physical_device, surface_stuff)?;
 
-    let surface_format = choose_swapchain_format(&swapchain_support.formats);
+    let surface_format = choose_swapchain_format(&swapchain_support.formats)?;
     let present_mode = choose_swapchain_present_mode(&swapchain_support.present_modes);
     let extent = choose_swapchain_extent(&swapchain_support.capabilities, window);
 
@@ -352,10 +383,7 @@ pub fn create_swapchain(
             (
                 vk::SharingMode::CONCURRENT,
                 2,
-                vec![
-                    queue_family.graphics_family.unwrap(),
-                    queue_family.present_family.unwrap(),
-                ],
+                vec![queue_family.graphics()?, queue_family.present()?],
             )
         } else {
             (vk::SharingMode::EXCLUSIVE, 0, vec![])
@@ -386,36 +414,39 @@ pub fn create_swapchain(
     let swapchain = unsafe {
         swapchain_loader
             .create_swapchain(&swapchain_create_info, None)
-            .expect("Failed to create Swapchain!")
+            .context("Failed to create Swapchain")?
     };
 
     let swapchain_images = unsafe {
         swapchain_loader
             .get_swapchain_images(swapchain)
-            .expect("Failed to get Swapchain Images.")
+            .context("Failed to get Swapchain Images")?
     };
 
-    SwapChainStuff {
+    Ok(SwapChainStuff {
         swapchain_loader,
         swapchain,
         swapchain_format: surface_format.format,
         swapchain_extent: extent,
         swapchain_images,
-    }
+    })
 }
 
 pub fn choose_swapchain_format(
-    available_formats: &Vec<vk::SurfaceFormatKHR>,
-) -> vk::SurfaceFormatKHR {
+    available_formats: &[vk::SurfaceFormatKHR],
+) -> Result<vk::SurfaceFormatKHR, GraphicsError> {
     for available_format in available_formats {
         if available_format.format == vk::Format::B8G8R8A8_SRGB
             && available_format.color_space == vk::ColorSpaceKHR::SRGB_NONLINEAR
         {
-            return *available_format;
+            return Ok(*available_format);
         }
     }
 
-    return *available_formats.first().unwrap();
+    available_formats
+        .first()
+        .copied()
+        .ok_or(GraphicsError::Internal("the surface offers no image format"))
 }
 
 pub fn choose_swapchain_present_mode(
@@ -434,14 +465,14 @@ pub fn choose_swapchain_extent(
     capabilities: &vk::SurfaceCapabilitiesKHR,
     window: &winit::window::Window,
 ) -> vk::Extent2D {
-    if capabilities.current_extent.width != u32::max_value() {
+    if capabilities.current_extent.width != u32::MAX {
         capabilities.current_extent
     } else {
         use num::clamp;
 
         let window_size = window.inner_size();
-        println!(
-            "\t\tInner Window Size: ({}, {})",
+        log::debug!(
+            "Inner window size: ({}, {})",
             window_size.width, window_size.height
         );
 
@@ -460,7 +491,7 @@ pub fn choose_swapchain_extent(
     }
 }
 
-pub fn create_shader_module(device: &ash::Device, code: Vec<u8>) -> vk::ShaderModule {
+pub fn create_shader_module(device: &ash::Device, code: Vec<u8>) -> Result<vk::ShaderModule, GraphicsError> {
     let shader_module_create_info = vk::ShaderModuleCreateInfo {
         s_type: vk::StructureType::SHADER_MODULE_CREATE_INFO,
         p_next: ptr::null(),
@@ -472,7 +503,7 @@ pub fn create_shader_module(device: &ash::Device, code: Vec<u8>) -> vk::ShaderMo
     unsafe {
         device
             .create_shader_module(&shader_module_create_info, None)
-            .expect("Failed to create Shader Module!")
+            .context("Failed to create Shader Module")
     }
 }
 
@@ -482,7 +513,7 @@ pub fn create_buffer(
     usage: vk::BufferUsageFlags,
     required_memory_properties: vk::MemoryPropertyFlags,
     device_memory_properties: &vk::PhysicalDeviceMemoryProperties,
-) -> (vk::Buffer, vk::DeviceMemory) {
+) -> Result<(vk::Buffer, vk::DeviceMemory), GraphicsError> {
     let buffer_create_info = vk::BufferCreateInfo {
         s_type: vk::StructureType::BUFFER_CREATE_INFO,
         p_next: ptr::null(),
@@ -497,7 +528,7 @@ pub fn create_buffer(
     let buffer = unsafe {
         device
             .create_buffer(&buffer_create_info, None)
-            .expect("Failed to create Vertex Buffer")
+            .context("Failed to create Vertex Buffer")?
     };
 
     let mem_requirements = unsafe { device.get_buffer_memory_requirements(buffer) };
@@ -505,7 +536,7 @@ pub fn create_buffer(
         mem_requirements.memory_type_bits,
         required_memory_properties,
         device_memory_properties,
-    );
+    )?;
 
     let allocate_info = vk::MemoryAllocateInfo {
         s_type: vk::StructureType::MEMORY_ALLOCATE_INFO,
@@ -517,16 +548,16 @@ pub fn create_buffer(
     let buffer_memory = unsafe {
         device
             .allocate_memory(&allocate_info, None)
-            .expect("Failed to allocate vertex buffer memory!")
+            .context("Failed to allocate vertex buffer memory")?
     };
 
     unsafe {
         device
             .bind_buffer_memory(buffer, buffer_memory, 0)
-            .expect("Failed to bind Buffer");
+            .context("Failed to bind Buffer")?;
     }
 
-    (buffer, buffer_memory)
+    Ok((buffer, buffer_memory))
 }
 
 pub fn copy_buffer(
@@ -536,8 +567,8 @@ pub fn copy_buffer(
     src_buffer: vk::Buffer,
     dst_buffer: vk::Buffer,
     size: vk::DeviceSize,
-) {
-    let command_buffer = begin_single_time_command(device, command_pool);
+) -> Result<(), GraphicsError> {
+    let command_buffer = begin_single_time_command(device, command_pool)?;
 
     let copy_regions = [vk::BufferCopy {
         src_offset: 0,
@@ -549,13 +580,13 @@ pub fn copy_buffer(
         device.cmd_copy_buffer(command_buffer, src_buffer, dst_buffer, &copy_regions);
     }
 
-    end_single_time_command(device, command_pool, submit_queue, command_buffer);
+    end_single_time_command(device, command_pool, submit_queue, command_buffer)
 }
 
 pub fn begin_single_time_command(
     device: &ash::Device,
     command_pool: vk::CommandPool,
-) -> vk::CommandBuffer {
+) -> Result<vk::CommandBuffer, GraphicsError> {
     let command_buffer_allocate_info = vk::CommandBufferAllocateInfo {
         s_type: vk::StructureType::COMMAND_BUFFER_ALLOCATE_INFO,
         p_next: ptr::null(),
@@ -567,7 +598,7 @@ pub fn begin_single_time_command(
     let command_buffer = unsafe {
         device
             .allocate_command_buffers(&command_buffer_allocate_info)
-            .expect("Failed to allocate Command Buffers!")
+            .context("Failed to allocate Command Buffers")?
     }[0];
 
     let command_buffer_begin_info = vk::CommandBufferBeginInfo {
@@ -580,10 +611,10 @@ pub fn begin_single_time_command(
     unsafe {
         device
             .begin_command_buffer(command_buffer, &command_buffer_begin_info)
-            .expect("Failed to begin recording Command Buffer at beginning!");
+            .context("Failed to begin recording Command Buffer at beginning")?;
     }
 
-    command_buffer
+    Ok(command_buffer)
 }
 
 pub fn end_single_time_command(
@@ -591,11 +622,11 @@ pub fn end_single_time_command(
     command_pool: vk::CommandPool,
     submit_queue: vk::Queue,
     command_buffer: vk::CommandBuffer,
-) {
+) -> Result<(), GraphicsError> {
     unsafe {
         device
             .end_command_buffer(command_buffer)
-            .expect("Failed to record Command Buffer at Ending!");
+            .context("Failed to record Command Buffer at Ending")?;
     }
 
     let buffers_to_submit = [command_buffer];
@@ -615,30 +646,32 @@ pub fn end_single_time_command(
     unsafe {
         device
             .queue_submit(submit_queue, &sumbit_infos, vk::Fence::null())
-            .expect("Failed to Queue Submit!");
+            .context("Failed to Queue Submit")?;
         device
             .queue_wait_idle(submit_queue)
-            .expect("Failed to wait Queue idle!");
+            .context("Failed to wait Queue idle")?;
         device.free_command_buffers(command_pool, &buffers_to_submit);
     }
+
+    Ok(())
 }
 
 pub fn find_memory_type(
     type_filter: u32,
     required_properties: vk::MemoryPropertyFlags,
     mem_properties: &vk::PhysicalDeviceMemoryProperties,
-) -> u32 {
+) -> Result<u32, GraphicsError> {
     for (i, memory_type) in mem_properties.memory_types.iter().enumerate() {
         if (type_filter & (1 << i)) > 0 && memory_type.property_flags.contains(required_properties)
         {
-            return i as u32;
+            return Ok(i as u32);
         }
     }
 
-    panic!("Failed to find suitable memory type!")
+    Err(GraphicsError::NoSuitableMemoryType)
 }
 
-pub fn create_render_pass(device: &ash::Device, surface_format: vk::Format) -> vk::RenderPass {
+pub fn create_render_pass(device: &ash::Device, surface_format: vk::Format) -> Result<vk::RenderPass, GraphicsError> {
     let color_attachment = vk::AttachmentDescription {
         format: surface_format,
         flags: vk::AttachmentDescriptionFlags::empty(),
@@ -696,7 +729,7 @@ pub fn create_render_pass(device: &ash::Device, surface_format: vk::Format) -> v
     unsafe {
         device
             .create_render_pass(&renderpass_create_info, None)
-            .expect("Failed to create render pass!")
+            .context("Failed to create render pass")
     }
 }
 
@@ -705,7 +738,7 @@ pub fn create_framebuffers(
     render_pass: vk::RenderPass,
     image_views: &[vk::ImageView],
     swapchain_extent: vk::Extent2D,
-) -> Vec<vk::Framebuffer> {
+) -> Result<Vec<vk::Framebuffer>, GraphicsError> {
     image_views
         .iter()
         .map(|&image_view| {
@@ -726,7 +759,7 @@ pub fn create_framebuffers(
             unsafe {
                 device
                     .create_framebuffer(&framebuffer_create_info, None)
-                    .expect("Failed to create Framebuffer!")
+                    .context("Failed to create Framebuffer")
             }
         })
         .collect()
@@ -735,31 +768,35 @@ pub fn create_framebuffers(
 pub fn create_command_pool(
     device: &ash::Device,
     queue_families: &QueueFamilyIndices,
-) -> vk::CommandPool {
+) -> Result<vk::CommandPool, GraphicsError> {
     let command_pool_create_info = vk::CommandPoolCreateInfo {
         s_type: vk::StructureType::COMMAND_POOL_CREATE_INFO,
         p_next: ptr::null(),
         flags: vk::CommandPoolCreateFlags::empty(),
-        queue_family_index: queue_families.graphics_family.unwrap(),
+        queue_family_index: queue_families.graphics()?,
     };
 
     unsafe {
         device
             .create_command_pool(&command_pool_create_info, None)
-            .expect("Failed to create Command Pool!")
+            .context("Failed to create Command Pool")
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_command_buffers(
     device: &ash::Device,
     command_pool: vk::CommandPool,
     graphics_pipeline: vk::Pipeline,
-    framebuffers: &Vec<vk::Framebuffer>,
+    framebuffers: &[vk::Framebuffer],
     render_pass: vk::RenderPass,
     surface_extent: vk::Extent2D,
     pipeline_layout: vk::PipelineLayout,
-    model_buffers: &Vec<ModelBuffers>,
-) -> Vec<vk::CommandBuffer> {
+    model_buffers: &[ModelBuffers],
+    text_pipeline: vk::Pipeline,
+    text_pipeline_layout: vk::PipelineLayout,
+    text_renderer: &TextRenderer,
+) -> Result<Vec<vk::CommandBuffer>, GraphicsError> {
     let command_buffer_allocate_info = vk::CommandBufferAllocateInfo {
         s_type: vk::StructureType::COMMAND_BUFFER_ALLOCATE_INFO,
         p_next: ptr::null(),
@@ -771,7 +808,7 @@ pub fn create_command_buffers(
     let command_buffers = unsafe {
         device
             .allocate_command_buffers(&command_buffer_allocate_info)
-            .expect("Failed to allocate Command Buffers!")
+            .context("Failed to allocate Command Buffers")?
     };
 
     for (i, &command_buffer) in command_buffers.iter().enumerate() {
@@ -785,7 +822,7 @@ pub fn create_command_buffers(
         unsafe {
             device
                 .begin_command_buffer(command_buffer, &command_buffer_begin_info)
-                .expect("Failed to begin recording Command Buffer!");
+                .context("Failed to begin recording Command Buffer")?;
         }
 
         let clear_values = [vk::ClearValue {
@@ -843,18 +880,39 @@ pub fn create_command_buffers(
                 device.cmd_draw_indexed(command_buffer, buffers.index_count, 1, 0, 0, 0);
             }
 
+            // Text goes last so it is drawn on top of the scene
+            let text_vertex_buffers = [text_renderer.vertex_buffers[i]];
+            let offsets = [0_u64];
+            let text_descriptor_sets = [text_renderer.descriptor_set];
+
+            device.cmd_bind_pipeline(
+                command_buffer,
+                vk::PipelineBindPoint::GRAPHICS,
+                text_pipeline,
+            );
+            device.cmd_bind_vertex_buffers(command_buffer, 0, &text_vertex_buffers, &offsets);
+            device.cmd_bind_descriptor_sets(
+                command_buffer,
+                vk::PipelineBindPoint::GRAPHICS,
+                text_pipeline_layout,
+                0,
+                &text_descriptor_sets,
+                &[],
+            );
+            device.cmd_draw(command_buffer, TextRenderer::vertex_count(), 1, 0, 0);
+
             device.cmd_end_render_pass(command_buffer);
 
             device
                 .end_command_buffer(command_buffer)
-                .expect("Failed to record Command Buffer at Ending!");
+                .context("Failed to record Command Buffer at Ending")?;
         }
     }
 
-    command_buffers
+    Ok(command_buffers)
 }
 
-pub fn create_sync_objects(device: &ash::Device, max_frame_in_flight: usize) -> SyncObjects {
+pub fn create_sync_objects(device: &ash::Device, max_frame_in_flight: usize) -> Result<SyncObjects, GraphicsError> {
     let mut sync_objects = SyncObjects {
         image_available_semaphores: vec![],
         render_finished_semaphores: vec![],
@@ -877,13 +935,13 @@ pub fn create_sync_objects(device: &ash::Device, max_frame_in_flight: usize) ->
         unsafe {
             let image_available_semaphore = device
                 .create_semaphore(&semaphore_create_info, None)
-                .expect("Failed to create Semaphore Object!");
+                .context("Failed to create Semaphore Object")?;
             let render_finished_semaphore = device
                 .create_semaphore(&semaphore_create_info, None)
-                .expect("Failed to create Semaphore Object!");
+                .context("Failed to create Semaphore Object")?;
             let inflight_fence = device
                 .create_fence(&fence_create_info, None)
-                .expect("Failed to create Fence Object!");
+                .context("Failed to create Fence Object")?;
 
             sync_objects
                 .image_available_semaphores
@@ -895,7 +953,7 @@ pub fn create_sync_objects(device: &ash::Device, max_frame_in_flight: usize) ->
         }
     }
 
-    sync_objects
+    Ok(sync_objects)
 }
 
 pub fn create_vertex_buffer<T>(
@@ -904,7 +962,7 @@ pub fn create_vertex_buffer<T>(
     command_pool: vk::CommandPool,
     submit_queue: vk::Queue,
     data: &[T],
-) -> (vk::Buffer, vk::DeviceMemory) {
+) -> Result<(vk::Buffer, vk::DeviceMemory), GraphicsError> {
     let buffer_size = ::std::mem::size_of_val(data) as vk::DeviceSize;
 
     let (staging_buffer, staging_buffer_memory) = create_buffer(
@@ -913,7 +971,7 @@ pub fn create_vertex_buffer<T>(
         vk::BufferUsageFlags::TRANSFER_SRC,
         vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
         device_memory_properties,
-    );
+    )?;
 
     unsafe {
         let data_ptr = device
@@ -923,7 +981,7 @@ pub fn create_vertex_buffer<T>(
                 buffer_size,
                 vk::MemoryMapFlags::empty(),
             )
-            .expect("Failed to Map Memory") as *mut T;
+            .context("Failed to Map Memory")? as *mut T;
 
         data_ptr.copy_from_nonoverlapping(data.as_ptr(), data.len());
 
@@ -936,7 +994,7 @@ pub fn create_vertex_buffer<T>(
         vk::BufferUsageFlags::TRANSFER_DST | vk::BufferUsageFlags::VERTEX_BUFFER,
         vk::MemoryPropertyFlags::DEVICE_LOCAL,
         device_memory_properties,
-    );
+    )?;
 
     copy_buffer(
         device,
@@ -945,14 +1003,14 @@ pub fn create_vertex_buffer<T>(
         staging_buffer,
         vertex_buffer,
         buffer_size,
-    );
+    )?;
 
     unsafe {
         device.destroy_buffer(staging_buffer, None);
         device.free_memory(staging_buffer_memory, None);
     }
 
-    (vertex_buffer, vertex_buffer_memory)
+    Ok((vertex_buffer, vertex_buffer_memory))
 }
 
 pub fn create_index_buffer(
@@ -961,7 +1019,7 @@ pub fn create_index_buffer(
     command_pool: vk::CommandPool,
     submit_queue: vk::Queue,
     data: &[u32],
-) -> (vk::Buffer, vk::DeviceMemory) {
+) -> Result<(vk::Buffer, vk::DeviceMemory), GraphicsError> {
     let buffer_size = ::std::mem::size_of_val(data) as vk::DeviceSize;
 
     let (staging_buffer, staging_buffer_memory) = create_buffer(
@@ -970,7 +1028,7 @@ pub fn create_index_buffer(
         vk::BufferUsageFlags::TRANSFER_SRC,
         vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
         device_memory_properties,
-    );
+    )?;
 
     unsafe {
         let data_ptr = device
@@ -980,7 +1038,7 @@ pub fn create_index_buffer(
                 buffer_size,
                 vk::MemoryMapFlags::empty(),
             )
-            .expect("Failed to Map Memory") as *mut u32;
+            .context("Failed to Map Memory")? as *mut u32;
 
         data_ptr.copy_from_nonoverlapping(data.as_ptr(), data.len());
 
@@ -993,7 +1051,7 @@ pub fn create_index_buffer(
         vk::BufferUsageFlags::TRANSFER_DST | vk::BufferUsageFlags::INDEX_BUFFER,
         vk::MemoryPropertyFlags::DEVICE_LOCAL,
         device_memory_properties,
-    );
+    )?;
 
     copy_buffer(
         device,
@@ -1002,20 +1060,20 @@ pub fn create_index_buffer(
         staging_buffer,
         index_buffer,
         buffer_size,
-    );
+    )?;
 
     unsafe {
         device.destroy_buffer(staging_buffer, None);
         device.free_memory(staging_buffer_memory, None);
     }
 
-    (index_buffer, index_buffer_memory)
+    Ok((index_buffer, index_buffer_memory))
 }
 
 pub fn create_descriptor_pool(
     device: &ash::Device,
     swapchain_images_size: usize,
-) -> vk::DescriptorPool {
+) -> Result<vk::DescriptorPool, GraphicsError> {
     let pool_sizes = [vk::DescriptorPoolSize {
         ty: vk::DescriptorType::UNIFORM_BUFFER,
         descriptor_count: swapchain_images_size as u32,
@@ -1033,7 +1091,7 @@ pub fn create_descriptor_pool(
     unsafe {
         device
             .create_descriptor_pool(&descriptor_pool_create_info, None)
-            .expect("Failed to create Descriptor Pool!")
+            .context("Failed to create Descriptor Pool")
     }
 }
 
@@ -1043,7 +1101,7 @@ pub fn create_descriptor_sets(
     descriptor_set_layout: vk::DescriptorSetLayout,
     uniforms_buffers: &[vk::Buffer],
     swapchain_images_size: usize,
-) -> Vec<vk::DescriptorSet> {
+) -> Result<Vec<vk::DescriptorSet>, GraphicsError> {
     let mut layouts: Vec<vk::DescriptorSetLayout> = vec![];
     for _ in 0..swapchain_images_size {
         layouts.push(descriptor_set_layout);
@@ -1060,7 +1118,7 @@ pub fn create_descriptor_sets(
     let descriptor_sets = unsafe {
         device
             .allocate_descriptor_sets(&descriptor_set_allocate_info)
-            .expect("Failed to allocate descriptor sets!")
+            .context("Failed to allocate descriptor sets")?
     };
 
     for (i, &descritptor_set) in descriptor_sets.iter().enumerate() {
@@ -1088,10 +1146,10 @@ pub fn create_descriptor_sets(
         }
     }
 
-    descriptor_sets
+    Ok(descriptor_sets)
 }
 
-pub fn create_descriptor_set_layout(device: &ash::Device) -> vk::DescriptorSetLayout {
+pub fn create_descriptor_set_layout(device: &ash::Device) -> Result<vk::DescriptorSetLayout, GraphicsError> {
     let ubo_layout_bindings = [vk::DescriptorSetLayoutBinding {
         binding: 0,
         descriptor_type: vk::DescriptorType::UNIFORM_BUFFER,
@@ -1111,7 +1169,7 @@ pub fn create_descriptor_set_layout(device: &ash::Device) -> vk::DescriptorSetLa
     unsafe {
         device
             .create_descriptor_set_layout(&ubo_layout_create_info, None)
-            .expect("Failed to create Descriptor Set Layout!")
+            .context("Failed to create Descriptor Set Layout")
     }
 }
 
@@ -1119,7 +1177,7 @@ pub fn create_uniform_buffers(
     device: &ash::Device,
     device_memory_properties: &vk::PhysicalDeviceMemoryProperties,
     swapchain_image_count: usize,
-) -> (Vec<vk::Buffer>, Vec<vk::DeviceMemory>) {
+) -> Result<(Vec<vk::Buffer>, Vec<vk::DeviceMemory>), GraphicsError> {
     let buffer_size = ::std::mem::size_of::<UniformBufferObject>();
 
     let mut uniform_buffers = vec![];
@@ -1132,20 +1190,20 @@ pub fn create_uniform_buffers(
             vk::BufferUsageFlags::UNIFORM_BUFFER,
             vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
             device_memory_properties,
-        );
+        )?;
         uniform_buffers.push(uniform_buffer);
         uniform_buffers_memory.push(uniform_buffer_memory);
     }
 
-    (uniform_buffers, uniform_buffers_memory)
+    Ok((uniform_buffers, uniform_buffers_memory))
 }
 
 pub fn create_image_views(
     device: &ash::Device,
     surface_format: vk::Format,
     images: &[vk::Image],
-) -> Vec<vk::ImageView> {
-    let swapchain_imageviews: Vec<vk::ImageView> = images
+) -> Result<Vec<vk::ImageView>, GraphicsError> {
+    images
         .iter()
         .map(|&image| {
             create_image_view(
@@ -1156,9 +1214,7 @@ pub fn create_image_views(
                 1,
             )
         })
-        .collect();
-
-    swapchain_imageviews
+        .collect()
 }
 
 pub fn create_image_view(
@@ -1167,7 +1223,7 @@ pub fn create_image_view(
     format: vk::Format,
     aspect_flags: vk::ImageAspectFlags,
     mip_levels: u32,
-) -> vk::ImageView {
+) -> Result<vk::ImageView, GraphicsError> {
     let imageview_create_info = vk::ImageViewCreateInfo {
         s_type: vk::StructureType::IMAGE_VIEW_CREATE_INFO,
         p_next: ptr::null(),
@@ -1193,7 +1249,7 @@ pub fn create_image_view(
     unsafe {
         device
             .create_image_view(&imageview_create_info, None)
-            .expect("Failed to create Image View!")
+            .context("Failed to create Image View")
     }
 }
 
@@ -1202,17 +1258,75 @@ pub fn create_graphics_pipeline(
     render_pass: vk::RenderPass,
     swapchain_extent: vk::Extent2D,
     ubo_set_layout: vk::DescriptorSetLayout,
-) -> (vk::Pipeline, vk::PipelineLayout) {
-    let vert_shader_module = create_shader_module(
+) -> Result<(vk::Pipeline, vk::PipelineLayout), GraphicsError> {
+    let binding_description = Vertex::get_binding_description();
+    let attribute_description = Vertex::get_attribute_descriptions();
+
+    create_pipeline(
         device,
-        include_bytes!("../../shaders/spv/main.vert.spv").to_vec(),
-    );
-    let frag_shader_module = create_shader_module(
+        render_pass,
+        swapchain_extent,
+        ubo_set_layout,
+        &PipelineDescription {
+            vertex_shader: include_bytes!("../../shaders/spv/main.vert.spv"),
+            fragment_shader: include_bytes!("../../shaders/spv/main.frag.spv"),
+            binding_descriptions: &binding_description,
+            attribute_descriptions: &attribute_description,
+            cull_mode: vk::CullModeFlags::BACK,
+            is_blend_enable: false,
+        },
+    )
+}
+
+pub fn create_text_pipeline(
+    device: &ash::Device,
+    render_pass: vk::RenderPass,
+    swapchain_extent: vk::Extent2D,
+    font_set_layout: vk::DescriptorSetLayout,
+) -> Result<(vk::Pipeline, vk::PipelineLayout), GraphicsError> {
+    let binding_description = TextVertex::get_binding_description();
+    let attribute_description = TextVertex::get_attribute_descriptions();
+
+    // Glyphs are blended over whatever the scene drew, and their winding doesn't matter
+    create_pipeline(
         device,
-        include_bytes!("../../shaders/spv/main.frag.spv").to_vec(),
-    );
+        render_pass,
+        swapchain_extent,
+        font_set_layout,
+        &PipelineDescription {
+            vertex_shader: include_bytes!("../../shaders/spv/text.vert.spv"),
+            fragment_shader: include_bytes!("../../shaders/spv/text.frag.spv"),
+            binding_descriptions: &binding_description,
+            attribute_descriptions: &attribute_description,
+            cull_mode: vk::CullModeFlags::NONE,
+            is_blend_enable: true,
+        },
+    )
+}
 
-    let main_function_name = CString::new("main").unwrap(); // the beginning function name in shader code.
+/// The parts of a graphics pipeline that differ between the pipelines of this renderer.
+struct PipelineDescription<'a> {
+    vertex_shader: &'a [u8],
+    fragment_shader: &'a [u8],
+    binding_descriptions: &'a [vk::VertexInputBindingDescription],
+    attribute_descriptions: &'a [vk::VertexInputAttributeDescription],
+    cull_mode: vk::CullModeFlags,
+    is_blend_enable: bool,
+}
+
+fn create_pipeline(
+    device: &ash::Device,
+    render_pass: vk::RenderPass,
+    swapchain_extent: vk::Extent2D,
+    set_layout: vk::DescriptorSetLayout,
+    description: &PipelineDescription,
+) -> Result<(vk::Pipeline, vk::PipelineLayout), GraphicsError> {
+    let vert_shader_module = create_shader_module(device, description.vertex_shader.to_vec())?;
+    let frag_shader_module = create_shader_module(device, description.fragment_shader.to_vec())?;
+
+    // the beginning function name in shader code.
+    let main_function_name = CString::new("main")
+        .map_err(|_| GraphicsError::Internal("the shader entry point holds a nul byte"))?;
 
     let shader_stages = [
         vk::PipelineShaderStageCreateInfo {
@@ -1237,17 +1351,14 @@ pub fn create_graphics_pipeline(
         },
     ];
 
-    let binding_description = Vertex::get_binding_description();
-    let attribute_description = Vertex::get_attribute_descriptions();
-
     let vertex_input_state_create_info = vk::PipelineVertexInputStateCreateInfo {
         s_type: vk::StructureType::PIPELINE_VERTEX_INPUT_STATE_CREATE_INFO,
         p_next: ptr::null(),
         flags: vk::PipelineVertexInputStateCreateFlags::empty(),
-        vertex_attribute_description_count: attribute_description.len() as u32,
-        p_vertex_attribute_descriptions: attribute_description.as_ptr(),
-        vertex_binding_description_count: binding_description.len() as u32,
-        p_vertex_binding_descriptions: binding_description.as_ptr(),
+        vertex_attribute_description_count: description.attribute_descriptions.len() as u32,
+        p_vertex_attribute_descriptions: description.attribute_descriptions.as_ptr(),
+        vertex_binding_description_count: description.binding_descriptions.len() as u32,
+        p_vertex_binding_descriptions: description.binding_descriptions.as_ptr(),
     };
     let vertex_input_assembly_state_info = vk::PipelineInputAssemblyStateCreateInfo {
         s_type: vk::StructureType::PIPELINE_INPUT_ASSEMBLY_STATE_CREATE_INFO,
@@ -1286,7 +1397,7 @@ pub fn create_graphics_pipeline(
         p_next: ptr::null(),
         flags: vk::PipelineRasterizationStateCreateFlags::empty(),
         depth_clamp_enable: vk::FALSE,
-        cull_mode: vk::CullModeFlags::BACK,
+        cull_mode: description.cull_mode,
         front_face: vk::FrontFace::CLOCKWISE,
         line_width: 1.0,
         polygon_mode: vk::PolygonMode::FILL,
@@ -1334,15 +1445,28 @@ pub fn create_graphics_pipeline(
         min_depth_bounds: 0.0,
     };
 
-    let color_blend_attachment_states = [vk::PipelineColorBlendAttachmentState {
-        blend_enable: vk::FALSE,
-        color_write_mask: vk::ColorComponentFlags::all(),
-        src_color_blend_factor: vk::BlendFactor::ONE,
-        dst_color_blend_factor: vk::BlendFactor::ZERO,
-        color_blend_op: vk::BlendOp::ADD,
-        src_alpha_blend_factor: vk::BlendFactor::ONE,
-        dst_alpha_blend_factor: vk::BlendFactor::ZERO,
-        alpha_blend_op: vk::BlendOp::ADD,
+    let color_blend_attachment_states = [if description.is_blend_enable {
+        vk::PipelineColorBlendAttachmentState {
+            blend_enable: vk::TRUE,
+            color_write_mask: vk::ColorComponentFlags::all(),
+            src_color_blend_factor: vk::BlendFactor::SRC_ALPHA,
+            dst_color_blend_factor: vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+            color_blend_op: vk::BlendOp::ADD,
+            src_alpha_blend_factor: vk::BlendFactor::ONE,
+            dst_alpha_blend_factor: vk::BlendFactor::ZERO,
+            alpha_blend_op: vk::BlendOp::ADD,
+        }
+    } else {
+        vk::PipelineColorBlendAttachmentState {
+            blend_enable: vk::FALSE,
+            color_write_mask: vk::ColorComponentFlags::all(),
+            src_color_blend_factor: vk::BlendFactor::ONE,
+            dst_color_blend_factor: vk::BlendFactor::ZERO,
+            color_blend_op: vk::BlendOp::ADD,
+            src_alpha_blend_factor: vk::BlendFactor::ONE,
+            dst_alpha_blend_factor: vk::BlendFactor::ZERO,
+            alpha_blend_op: vk::BlendOp::ADD,
+        }
     }];
 
     let color_blend_state = vk::PipelineColorBlendStateCreateInfo {
@@ -1356,7 +1480,7 @@ pub fn create_graphics_pipeline(
         blend_constants: [0.0, 0.0, 0.0, 0.0],
     };
 
-    let set_layouts = [ubo_set_layout];
+    let set_layouts = [set_layout];
 
     let pipeline_layout_create_info = vk::PipelineLayoutCreateInfo {
         s_type: vk::StructureType::PIPELINE_LAYOUT_CREATE_INFO,
@@ -1371,7 +1495,7 @@ pub fn create_graphics_pipeline(
     let pipeline_layout = unsafe {
         device
             .create_pipeline_layout(&pipeline_layout_create_info, None)
-            .expect("Failed to create pipeline layout!")
+            .context("Failed to create pipeline layout")?
     };
 
     let graphic_pipeline_create_infos = [vk::GraphicsPipelineCreateInfo {
@@ -1403,7 +1527,8 @@ pub fn create_graphics_pipeline(
                 &graphic_pipeline_create_infos,
                 None,
             )
-            .expect("Failed to create Graphics Pipeline!.")
+            .map_err(|(_, result)| result)
+            .context("Failed to create Graphics Pipeline")?
     };
 
     unsafe {
@@ -1411,5 +1536,465 @@ pub fn create_graphics_pipeline(
         device.destroy_shader_module(frag_shader_module, None);
     }
 
-    (graphics_pipelines[0], pipeline_layout)
+    Ok((graphics_pipelines[0], pipeline_layout))
+}
+
+pub fn create_image(
+    device: &ash::Device,
+    width: u32,
+    height: u32,
+    format: vk::Format,
+    usage: vk::ImageUsageFlags,
+    required_memory_properties: vk::MemoryPropertyFlags,
+    device_memory_properties: &vk::PhysicalDeviceMemoryProperties,
+) -> Result<(vk::Image, vk::DeviceMemory), GraphicsError> {
+    let image_create_info = vk::ImageCreateInfo {
+        s_type: vk::StructureType::IMAGE_CREATE_INFO,
+        p_next: ptr::null(),
+        flags: vk::ImageCreateFlags::empty(),
+        image_type: vk::ImageType::TYPE_2D,
+        format,
+        extent: vk::Extent3D {
+            width,
+            height,
+            depth: 1,
+        },
+        mip_levels: 1,
+        array_layers: 1,
+        samples: vk::SampleCountFlags::TYPE_1,
+        tiling: vk::ImageTiling::OPTIMAL,
+        usage,
+        sharing_mode: vk::SharingMode::EXCLUSIVE,
+        queue_family_index_count: 0,
+        p_queue_family_indices: ptr::null(),
+        initial_layout: vk::ImageLayout::UNDEFINED,
+    };
+
+    let image = unsafe {
+        device
+            .create_image(&image_create_info, None)
+            .context("Failed to create Image")?
+    };
+
+    let image_memory_requirement = unsafe { device.get_image_memory_requirements(image) };
+    let memory_allocate_info = vk::MemoryAllocateInfo {
+        s_type: vk::StructureType::MEMORY_ALLOCATE_INFO,
+        p_next: ptr::null(),
+        allocation_size: image_memory_requirement.size,
+        memory_type_index: find_memory_type(
+            image_memory_requirement.memory_type_bits,
+            required_memory_properties,
+            device_memory_properties,
+        )?,
+    };
+
+    let image_memory = unsafe {
+        device
+            .allocate_memory(&memory_allocate_info, None)
+            .context("Failed to allocate Image Memory")?
+    };
+
+    unsafe {
+        device
+            .bind_image_memory(image, image_memory, 0)
+            .context("Failed to bind Image Memmory")?;
+    }
+
+    Ok((image, image_memory))
+}
+
+pub fn transition_image_layout(
+    device: &ash::Device,
+    command_pool: vk::CommandPool,
+    submit_queue: vk::Queue,
+    image: vk::Image,
+    old_layout: vk::ImageLayout,
+    new_layout: vk::ImageLayout,
+) -> Result<(), GraphicsError> {
+    let (src_access_mask, dst_access_mask, source_stage, destination_stage) =
+        if old_layout == vk::ImageLayout::UNDEFINED
+            && new_layout == vk::ImageLayout::TRANSFER_DST_OPTIMAL
+        {
+            (
+                vk::AccessFlags::empty(),
+                vk::AccessFlags::TRANSFER_WRITE,
+                vk::PipelineStageFlags::TOP_OF_PIPE,
+                vk::PipelineStageFlags::TRANSFER,
+            )
+        } else if old_layout == vk::ImageLayout::TRANSFER_DST_OPTIMAL
+            && new_layout == vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+        {
+            (
+                vk::AccessFlags::TRANSFER_WRITE,
+                vk::AccessFlags::SHADER_READ,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::PipelineStageFlags::FRAGMENT_SHADER,
+            )
+        } else {
+            return Err(GraphicsError::Internal("unsupported image layout transition"));
+        };
+
+    let command_buffer = begin_single_time_command(device, command_pool)?;
+
+    let image_barriers = [vk::ImageMemoryBarrier {
+        s_type: vk::StructureType::IMAGE_MEMORY_BARRIER,
+        p_next: ptr::null(),
+        src_access_mask,
+        dst_access_mask,
+        old_layout,
+        new_layout,
+        src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+        dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+        image,
+        subresource_range: vk::ImageSubresourceRange {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            base_mip_level: 0,
+            level_count: 1,
+            base_array_layer: 0,
+            layer_count: 1,
+        },
+    }];
+
+    unsafe {
+        device.cmd_pipeline_barrier(
+            command_buffer,
+            source_stage,
+            destination_stage,
+            vk::DependencyFlags::empty(),
+            &[],
+            &[],
+            &image_barriers,
+        );
+    }
+
+    end_single_time_command(device, command_pool, submit_queue, command_buffer)
+}
+
+pub fn copy_buffer_to_image(
+    device: &ash::Device,
+    command_pool: vk::CommandPool,
+    submit_queue: vk::Queue,
+    buffer: vk::Buffer,
+    image: vk::Image,
+    width: u32,
+    height: u32,
+) -> Result<(), GraphicsError> {
+    let command_buffer = begin_single_time_command(device, command_pool)?;
+
+    let buffer_image_regions = [vk::BufferImageCopy {
+        image_subresource: vk::ImageSubresourceLayers {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            mip_level: 0,
+            base_array_layer: 0,
+            layer_count: 1,
+        },
+        image_extent: vk::Extent3D {
+            width,
+            height,
+            depth: 1,
+        },
+        buffer_offset: 0,
+        buffer_image_height: 0,
+        buffer_row_length: 0,
+        image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+    }];
+
+    unsafe {
+        device.cmd_copy_buffer_to_image(
+            command_buffer,
+            buffer,
+            image,
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            &buffer_image_regions,
+        );
+    }
+
+    end_single_time_command(device, command_pool, submit_queue, command_buffer)
+}
+
+/// Uploads single channel `pixels` into a sampled image, ready to be read by shaders.
+pub fn create_texture_image(
+    device: &ash::Device,
+    device_memory_properties: &vk::PhysicalDeviceMemoryProperties,
+    command_pool: vk::CommandPool,
+    submit_queue: vk::Queue,
+    width: u32,
+    height: u32,
+    pixels: &[u8],
+) -> Result<(vk::Image, vk::DeviceMemory), GraphicsError> {
+    let buffer_size = pixels.len() as vk::DeviceSize;
+
+    let (staging_buffer, staging_buffer_memory) = create_buffer(
+        device,
+        buffer_size,
+        vk::BufferUsageFlags::TRANSFER_SRC,
+        vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        device_memory_properties,
+    )?;
+
+    unsafe {
+        let data_ptr = device
+            .map_memory(
+                staging_buffer_memory,
+                0,
+                buffer_size,
+                vk::MemoryMapFlags::empty(),
+            )
+            .context("Failed to Map Memory")? as *mut u8;
+
+        data_ptr.copy_from_nonoverlapping(pixels.as_ptr(), pixels.len());
+
+        device.unmap_memory(staging_buffer_memory);
+    }
+
+    let (texture_image, texture_image_memory) = create_image(
+        device,
+        width,
+        height,
+        vk::Format::R8_UNORM,
+        vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED,
+        vk::MemoryPropertyFlags::DEVICE_LOCAL,
+        device_memory_properties,
+    )?;
+
+    transition_image_layout(
+        device,
+        command_pool,
+        submit_queue,
+        texture_image,
+        vk::ImageLayout::UNDEFINED,
+        vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+    )?;
+    copy_buffer_to_image(
+        device,
+        command_pool,
+        submit_queue,
+        staging_buffer,
+        texture_image,
+        width,
+        height,
+    )?;
+    transition_image_layout(
+        device,
+        command_pool,
+        submit_queue,
+        texture_image,
+        vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+        vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+    )?;
+
+    unsafe {
+        device.destroy_buffer(staging_buffer, None);
+        device.free_memory(staging_buffer_memory, None);
+    }
+
+    Ok((texture_image, texture_image_memory))
+}
+
+/// Sampler for pixel art: no filtering, no repetition.
+pub fn create_texture_sampler(device: &ash::Device) -> Result<vk::Sampler, GraphicsError> {
+    let sampler_create_info = vk::SamplerCreateInfo {
+        s_type: vk::StructureType::SAMPLER_CREATE_INFO,
+        p_next: ptr::null(),
+        flags: vk::SamplerCreateFlags::empty(),
+        mag_filter: vk::Filter::NEAREST,
+        min_filter: vk::Filter::NEAREST,
+        mipmap_mode: vk::SamplerMipmapMode::NEAREST,
+        address_mode_u: vk::SamplerAddressMode::CLAMP_TO_EDGE,
+        address_mode_v: vk::SamplerAddressMode::CLAMP_TO_EDGE,
+        address_mode_w: vk::SamplerAddressMode::CLAMP_TO_EDGE,
+        mip_lod_bias: 0.0,
+        anisotropy_enable: vk::FALSE,
+        max_anisotropy: 1.0,
+        compare_enable: vk::FALSE,
+        compare_op: vk::CompareOp::ALWAYS,
+        min_lod: 0.0,
+        max_lod: 0.0,
+        border_color: vk::BorderColor::INT_OPAQUE_BLACK,
+        unnormalized_coordinates: vk::FALSE,
+    };
+
+    unsafe {
+        device
+            .create_sampler(&sampler_create_info, None)
+            .context("Failed to create Sampler")
+    }
+}
+
+pub fn create_text_descriptor_set_layout(device: &ash::Device) -> Result<vk::DescriptorSetLayout, GraphicsError> {
+    let font_layout_bindings = [
+        vk::DescriptorSetLayoutBinding {
+            binding: 0,
+            descriptor_type: vk::DescriptorType::SAMPLED_IMAGE,
+            descriptor_count: 1,
+            stage_flags: vk::ShaderStageFlags::FRAGMENT,
+            p_immutable_samplers: ptr::null(),
+        },
+        vk::DescriptorSetLayoutBinding {
+            binding: 1,
+            descriptor_type: vk::DescriptorType::SAMPLER,
+            descriptor_count: 1,
+            stage_flags: vk::ShaderStageFlags::FRAGMENT,
+            p_immutable_samplers: ptr::null(),
+        },
+    ];
+
+    let font_layout_create_info = vk::DescriptorSetLayoutCreateInfo {
+        s_type: vk::StructureType::DESCRIPTOR_SET_LAYOUT_CREATE_INFO,
+        p_next: ptr::null(),
+        flags: vk::DescriptorSetLayoutCreateFlags::empty(),
+        binding_count: font_layout_bindings.len() as u32,
+        p_bindings: font_layout_bindings.as_ptr(),
+    };
+
+    unsafe {
+        device
+            .create_descriptor_set_layout(&font_layout_create_info, None)
+            .context("Failed to create Descriptor Set Layout")
+    }
+}
+
+/// Descriptor pool and set binding the font atlas, which is shared by every frame.
+pub fn create_text_descriptor_set(
+    device: &ash::Device,
+    descriptor_set_layout: vk::DescriptorSetLayout,
+    atlas_image_view: vk::ImageView,
+    sampler: vk::Sampler,
+) -> Result<(vk::DescriptorPool, vk::DescriptorSet), GraphicsError> {
+    let pool_sizes = [
+        vk::DescriptorPoolSize {
+            ty: vk::DescriptorType::SAMPLED_IMAGE,
+            descriptor_count: 1,
+        },
+        vk::DescriptorPoolSize {
+            ty: vk::DescriptorType::SAMPLER,
+            descriptor_count: 1,
+        },
+    ];
+
+    let descriptor_pool_create_info = vk::DescriptorPoolCreateInfo {
+        s_type: vk::StructureType::DESCRIPTOR_POOL_CREATE_INFO,
+        p_next: ptr::null(),
+        flags: vk::DescriptorPoolCreateFlags::empty(),
+        max_sets: 1,
+        pool_size_count: pool_sizes.len() as u32,
+        p_pool_sizes: pool_sizes.as_ptr(),
+    };
+
+    let descriptor_pool = unsafe {
+        device
+            .create_descriptor_pool(&descriptor_pool_create_info, None)
+            .context("Failed to create Descriptor Pool")?
+    };
+
+    let layouts = [descriptor_set_layout];
+    let descriptor_set_allocate_info = vk::DescriptorSetAllocateInfo {
+        s_type: vk::StructureType::DESCRIPTOR_SET_ALLOCATE_INFO,
+        p_next: ptr::null(),
+        descriptor_pool,
+        descriptor_set_count: layouts.len() as u32,
+        p_set_layouts: layouts.as_ptr(),
+    };
+
+    let descriptor_set = unsafe {
+        device
+            .allocate_descriptor_sets(&descriptor_set_allocate_info)
+            .context("Failed to allocate descriptor sets")?
+    }[0];
+
+    let descriptor_image_infos = [vk::DescriptorImageInfo {
+        sampler: vk::Sampler::null(),
+        image_view: atlas_image_view,
+        image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+    }];
+    let descriptor_sampler_infos = [vk::DescriptorImageInfo {
+        sampler,
+        image_view: vk::ImageView::null(),
+        image_layout: vk::ImageLayout::UNDEFINED,
+    }];
+
+    let descriptor_write_sets = [
+        vk::WriteDescriptorSet {
+            s_type: vk::StructureType::WRITE_DESCRIPTOR_SET,
+            p_next: ptr::null(),
+            dst_set: descriptor_set,
+            dst_binding: 0,
+            dst_array_element: 0,
+            descriptor_count: 1,
+            descriptor_type: vk::DescriptorType::SAMPLED_IMAGE,
+            p_image_info: descriptor_image_infos.as_ptr(),
+            p_buffer_info: ptr::null(),
+            p_texel_buffer_view: ptr::null(),
+        },
+        vk::WriteDescriptorSet {
+            s_type: vk::StructureType::WRITE_DESCRIPTOR_SET,
+            p_next: ptr::null(),
+            dst_set: descriptor_set,
+            dst_binding: 1,
+            dst_array_element: 0,
+            descriptor_count: 1,
+            descriptor_type: vk::DescriptorType::SAMPLER,
+            p_image_info: descriptor_sampler_infos.as_ptr(),
+            p_buffer_info: ptr::null(),
+            p_texel_buffer_view: ptr::null(),
+        },
+    ];
+
+    unsafe {
+        device.update_descriptor_sets(&descriptor_write_sets, &[]);
+    }
+
+    Ok((descriptor_pool, descriptor_set))
+}
+
+/// Vertex buffers the CPU rewrites every frame, one per swapchain image.
+pub fn create_dynamic_vertex_buffers(
+    device: &ash::Device,
+    device_memory_properties: &vk::PhysicalDeviceMemoryProperties,
+    buffer_size: vk::DeviceSize,
+    swapchain_image_count: usize,
+) -> Result<(Vec<vk::Buffer>, Vec<vk::DeviceMemory>), GraphicsError> {
+    let mut vertex_buffers = vec![];
+    let mut vertex_buffers_memory = vec![];
+
+    for _ in 0..swapchain_image_count {
+        let (vertex_buffer, vertex_buffer_memory) = create_buffer(
+            device,
+            buffer_size,
+            vk::BufferUsageFlags::VERTEX_BUFFER,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+            device_memory_properties,
+        )?;
+        vertex_buffers.push(vertex_buffer);
+        vertex_buffers_memory.push(vertex_buffer_memory);
+    }
+
+    Ok((vertex_buffers, vertex_buffers_memory))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swapchain_format_prefers_srgb_and_needs_one() {
+        let unorm = vk::SurfaceFormatKHR {
+            format: vk::Format::B8G8R8A8_UNORM,
+            color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+        };
+        let srgb = vk::SurfaceFormatKHR {
+            format: vk::Format::B8G8R8A8_SRGB,
+            ..unorm
+        };
+
+        let format = |formats: &[vk::SurfaceFormatKHR]| {
+            choose_swapchain_format(formats).map(|chosen| chosen.format)
+        };
+        assert_eq!(format(&[unorm, srgb]).unwrap(), srgb.format);
+        assert_eq!(format(&[unorm]).unwrap(), unorm.format);
+        assert!(matches!(
+            choose_swapchain_format(&[]),
+            Err(GraphicsError::Internal(_))
+        ));
+    }
 }
@@ -3,6 +3,9 @@ use cgmath::Matrix4;
 
 use memoffset::offset_of;
 
+use crate::graphics_manager::error::GraphicsError;
+use crate::mesh::Vertex;
+
 pub struct DeviceExtension {
     pub names: [&'static str; 1],
     //    pub raw_names: [*const i8; 1],
@@ -11,9 +14,6 @@ pub struct DeviceExtension {
 pub struct SurfaceStuff {
     pub surface_loader: ash::extensions::khr::Surface,
     pub surface: vk::SurfaceKHR,
-
-    pub screen_width: u32,
-    pub screen_height: u32,
 }
 pub struct SwapChainStuff {
     pub swapchain_loader: ash::extensions::khr::Swapchain,
@@ -45,6 +45,16 @@ impl QueueFamilyIndices {
     pub fn is_complete(&self) -> bool {
         self.graphics_family.is_some() && self.present_family.is_some()
     }
+
+    pub fn graphics(&self) -> Result<u32, GraphicsError> {
+        self.graphics_family
+            .ok_or(GraphicsError::Internal("the device has no graphics queue family"))
+    }
+
+    pub fn present(&self) -> Result<u32, GraphicsError> {
+        self.present_family
+            .ok_or(GraphicsError::Internal("the device has no present queue family"))
+    }
 }
 
 pub struct SyncObjects {
@@ -53,12 +63,6 @@ pub struct SyncObjects {
     pub inflight_fences: Vec<vk::Fence>,
 }
 
-#[derive(Clone)]
-pub struct ModelMesh {
-    pub vertices: [Vertex; 4],
-    pub indices: [u32; 6],
-}
-
 #[repr(C)]
 #[derive(Clone, Debug, Copy)]
 pub struct UniformBufferObject {
@@ -67,12 +71,6 @@ pub struct UniformBufferObject {
     pub proj: Matrix4<f32>,
 }
 
-#[repr(C)]
-#[derive(Clone, Debug, Copy)]
-pub struct Vertex {
-    pub pos: [f32; 2],
-    pub color: [f32; 3],
-}
 impl Vertex {
     pub fn get_binding_description() -> [vk::VertexInputBindingDescription; 1] {
         [vk::VertexInputBindingDescription {
@@ -99,3 +97,43 @@ impl Vertex {
         ]
     }
 }
+
+#[repr(C)]
+#[derive(Clone, Debug, Copy, Default)]
+pub struct TextVertex {
+    pub pos: [f32; 2],
+    pub tex_coord: [f32; 2],
+    pub color: [f32; 4],
+}
+impl TextVertex {
+    pub fn get_binding_description() -> [vk::VertexInputBindingDescription; 1] {
+        [vk::VertexInputBindingDescription {
+            binding: 0,
+            stride: ::std::mem::size_of::<TextVertex>() as u32,
+            input_rate: vk::VertexInputRate::VERTEX,
+        }]
+    }
+
+    pub fn get_attribute_descriptions() -> [vk::VertexInputAttributeDescription; 3] {
+        [
+            vk::VertexInputAttributeDescription {
+                binding: 0,
+                location: 0,
+                format: vk::Format::R32G32_SFLOAT,
+                offset: offset_of!(TextVertex, pos) as u32,
+            },
+            vk::VertexInputAttributeDescription {
+                binding: 0,
+                location: 1,
+                format: vk::Format::R32G32_SFLOAT,
+                offset: offset_of!(TextVertex, tex_coord) as u32,
+            },
+            vk::VertexInputAttributeDescription {
+                binding: 0,
+                location: 2,
+                format: vk::Format::R32G32B32A32_SFLOAT,
+                offset: offset_of!(TextVertex, color) as u32,
+            },
+        ]
+    }
+}
@@ -0,0 +1,379 @@
+use ash::version::DeviceV1_0;
+use ash::vk;
+
+use crate::graphics_manager::error::{GraphicsError, VkResultExt};
+use crate::graphics_manager::share;
+use crate::graphics_manager::structures::TextVertex;
+
+/// Public domain misc-fixed 8x13 font from X11, as a binary PGM with the
+/// printable ASCII characters laid out in rows of `ATLAS_COLUMNS` glyphs.
+const FONT_ATLAS: &[u8] = include_bytes!("../../assets/fonts/font_8x13.pgm");
+pub const GLYPH_WIDTH: f32 = 8.0;
+pub const GLYPH_HEIGHT: f32 = 13.0;
+const ATLAS_COLUMNS: u32 = 16;
+const FIRST_GLYPH: char = ' ';
+const LAST_GLYPH: char = '~';
+const FALLBACK_GLYPH: char = '?';
+
+/// Glyphs that fit in the text vertex buffers, extra ones are dropped.
+pub const MAX_GLYPHS: usize = 1024;
+const VERTICES_PER_GLYPH: usize = 6;
+
+/// A string to draw on top of the scene.
+///
+/// `position` is the top left corner of the first glyph in pixels from the top
+/// left corner of the window, and `scale` multiplies the size of the font.
+#[derive(Clone, Debug)]
+pub struct Text {
+    pub content: String,
+    pub position: [f32; 2],
+    pub scale: f32,
+    pub color: [f32; 4],
+}
+
+/// Size in pixels of `content` when drawn with `scale`.
+pub fn text_size(content: &str, scale: f32) -> [f32; 2] {
+    let columns = content
+        .lines()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let rows = content.lines().count();
+
+    [
+        columns as f32 * GLYPH_WIDTH * scale,
+        rows as f32 * GLYPH_HEIGHT * scale,
+    ]
+}
+
+struct FontAtlas<'a> {
+    width: u32,
+    height: u32,
+    pixels: &'a [u8],
+}
+
+/// Reads a binary PGM with 8 bits per pixel.
+fn parse_pgm(data: &[u8]) -> Result<FontAtlas<'_>, GraphicsError> {
+    let invalid = || GraphicsError::Internal("the font atlas is not an 8 bit binary PGM");
+    let mut header = vec![];
+    let mut offset = 0;
+    // magic number, width, height and max value, each followed by a single whitespace
+    while header.len() < 4 {
+        let start = offset;
+        while !data.get(offset).ok_or_else(invalid)?.is_ascii_whitespace() {
+            offset += 1;
+        }
+        header.push(std::str::from_utf8(&data[start..offset]).map_err(|_| invalid())?);
+        offset += 1;
+    }
+
+    if header[0] != "P5" || header[3] != "255" {
+        return Err(invalid());
+    }
+    let width: u32 = header[1].parse().map_err(|_| invalid())?;
+    let height: u32 = header[2].parse().map_err(|_| invalid())?;
+    let pixels = data
+        .get(offset..offset + (width * height) as usize)
+        .ok_or_else(invalid)?;
+
+    Ok(FontAtlas {
+        width,
+        height,
+        pixels,
+    })
+}
+
+/// Builds two triangles per glyph of `text`, in normalized device coordinates
+/// for a surface of `extent`.
+fn layout_text(text: &Text, extent: vk::Extent2D, vertices: &mut Vec<TextVertex>) {
+    let atlas_rows = ((LAST_GLYPH as u32 - FIRST_GLYPH as u32) / ATLAS_COLUMNS + 1) as f32;
+    let glyph_width = GLYPH_WIDTH * text.scale;
+    let glyph_height = GLYPH_HEIGHT * text.scale;
+    let to_ndc = |x: f32, y: f32| {
+        [
+            x / extent.width as f32 * 2.0 - 1.0,
+            y / extent.height as f32 * 2.0 - 1.0,
+        ]
+    };
+
+    for (row, line) in text.content.lines().enumerate() {
+        for (column, character) in line.chars().enumerate() {
+            if vertices.len() + VERTICES_PER_GLYPH > MAX_GLYPHS * VERTICES_PER_GLYPH {
+                return;
+            }
+            if character == ' ' {
+                continue;
+            }
+
+            let character = if (FIRST_GLYPH..=LAST_GLYPH).contains(&character) {
+                character
+            } else {
+                FALLBACK_GLYPH
+            };
+            let index = character as u32 - FIRST_GLYPH as u32;
+            let u0 = (index % ATLAS_COLUMNS) as f32 / ATLAS_COLUMNS as f32;
+            let v0 = (index / ATLAS_COLUMNS) as f32 / atlas_rows;
+            let u1 = u0 + 1.0 / ATLAS_COLUMNS as f32;
+            let v1 = v0 + 1.0 / atlas_rows;
+
+            let x0 = text.position[0] + column as f32 * glyph_width;
+            let y0 = text.position[1] + row as f32 * glyph_height;
+            let x1 = x0 + glyph_width;
+            let y1 = y0 + glyph_height;
+
+            let corner = |x: f32, y: f32, u: f32, v: f32| TextVertex {
+                pos: to_ndc(x, y),
+                tex_coord: [u, v],
+                color: text.color,
+            };
+            vertices.extend_from_slice(&[
+                corner(x0, y0, u0, v0),
+                corner(x0, y1, u0, v1),
+                corner(x1, y1, u1, v1),
+                corner(x1, y1, u1, v1),
+                corner(x1, y0, u1, v0),
+                corner(x0, y0, u0, v0),
+            ]);
+        }
+    }
+}
+
+/// Everything needed to draw strings with the embedded bitmap font.
+///
+/// Texts are queued during a frame and written to the vertex buffer of the
+/// swapchain image being drawn, so they only live for a single frame.
+pub struct TextRenderer {
+    atlas_image: vk::Image,
+    atlas_image_memory: vk::DeviceMemory,
+    atlas_image_view: vk::ImageView,
+    sampler: vk::Sampler,
+
+    pub descriptor_set_layout: vk::DescriptorSetLayout,
+    descriptor_pool: vk::DescriptorPool,
+    pub descriptor_set: vk::DescriptorSet,
+
+    pub vertex_buffers: Vec<vk::Buffer>,
+    vertex_buffers_memory: Vec<vk::DeviceMemory>,
+
+    queued_texts: Vec<Text>,
+}
+
+impl TextRenderer {
+    pub fn new(
+        device: &ash::Device,
+        device_memory_properties: &vk::PhysicalDeviceMemoryProperties,
+        command_pool: vk::CommandPool,
+        submit_queue: vk::Queue,
+        swapchain_image_count: usize,
+    ) -> Result<TextRenderer, GraphicsError> {
+        let atlas = parse_pgm(FONT_ATLAS)?;
+        let (atlas_image, atlas_image_memory) = share::create_texture_image(
+            device,
+            device_memory_properties,
+            command_pool,
+            submit_queue,
+            atlas.width,
+            atlas.height,
+            atlas.pixels,
+        )?;
+        let atlas_image_view = share::create_image_view(
+            device,
+            atlas_image,
+            vk::Format::R8_UNORM,
+            vk::ImageAspectFlags::COLOR,
+            1,
+        )?;
+        let sampler = share::create_texture_sampler(device)?;
+
+        let descriptor_set_layout = share::create_text_descriptor_set_layout(device)?;
+        let (descriptor_pool, descriptor_set) = share::create_text_descriptor_set(
+            device,
+            descriptor_set_layout,
+            atlas_image_view,
+            sampler,
+        )?;
+
+        let (vertex_buffers, vertex_buffers_memory) = share::create_dynamic_vertex_buffers(
+            device,
+            device_memory_properties,
+            Self::vertex_buffer_size(),
+            swapchain_image_count,
+        )?;
+
+        Ok(TextRenderer {
+            atlas_image,
+            atlas_image_memory,
+            atlas_image_view,
+            sampler,
+
+            descriptor_set_layout,
+            descriptor_pool,
+            descriptor_set,
+
+            vertex_buffers,
+            vertex_buffers_memory,
+
+            queued_texts: vec![],
+        })
+    }
+
+    /// Number of vertices drawn from each vertex buffer, used ones or not.
+    pub fn vertex_count() -> u32 {
+        (MAX_GLYPHS * VERTICES_PER_GLYPH) as u32
+    }
+
+    fn vertex_buffer_size() -> vk::DeviceSize {
+        (std::mem::size_of::<TextVertex>() * MAX_GLYPHS * VERTICES_PER_GLYPH) as vk::DeviceSize
+    }
+
+    pub fn queue(&mut self, text: Text) {
+        self.queued_texts.push(text);
+    }
+
+    /// Writes the queued texts to the vertex buffer of `current_image` and empties the queue.
+    pub fn update_vertex_buffer(
+        &mut self,
+        device: &ash::Device,
+        current_image: usize,
+        extent: vk::Extent2D,
+    ) -> Result<(), GraphicsError> {
+        let mut vertices = Vec::with_capacity(MAX_GLYPHS * VERTICES_PER_GLYPH);
+        for text in self.queued_texts.drain(..) {
+            layout_text(&text, extent, &mut vertices);
+        }
+        // Unused vertices collapse to a point, so nothing of them gets drawn
+        vertices.resize(MAX_GLYPHS * VERTICES_PER_GLYPH, TextVertex::default());
+
+        unsafe {
+            let data_ptr = device
+                .map_memory(
+                    self.vertex_buffers_memory[current_image],
+                    0,
+                    Self::vertex_buffer_size(),
+                    vk::MemoryMapFlags::empty(),
+                )
+                .context("Failed to Map Memory")? as *mut TextVertex;
+
+            data_ptr.copy_from_nonoverlapping(vertices.as_ptr(), vertices.len());
+
+            device.unmap_memory(self.vertex_buffers_memory[current_image]);
+        }
+
+        Ok(())
+    }
+
+    /// Drops the texts queued for a frame that won't be drawn.
+    pub fn discard_queue(&mut self) {
+        self.queued_texts.clear();
+    }
+
+    pub fn destroy(&self, device: &ash::Device) {
+        unsafe {
+            for i in 0..self.vertex_buffers.len() {
+                device.destroy_buffer(self.vertex_buffers[i], None);
+                device.free_memory(self.vertex_buffers_memory[i], None);
+            }
+
+            device.destroy_descriptor_pool(self.descriptor_pool, None);
+            device.destroy_descriptor_set_layout(self.descriptor_set_layout, None);
+
+            device.destroy_sampler(self.sampler, None);
+            device.destroy_image_view(self.atlas_image_view, None);
+            device.destroy_image(self.atlas_image, None);
+            device.free_memory(self.atlas_image_memory, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXTENT: vk::Extent2D = vk::Extent2D {
+        width: 800,
+        height: 600,
+    };
+
+    fn text(content: &str) -> Text {
+        Text {
+            content: content.to_string(),
+            position: [0.0, 0.0],
+            scale: 1.0,
+            color: [1.0, 1.0, 1.0, 1.0],
+        }
+    }
+
+    #[test]
+    fn embedded_atlas_holds_every_glyph() {
+        let atlas = parse_pgm(FONT_ATLAS).unwrap();
+        let glyph_count = LAST_GLYPH as u32 - FIRST_GLYPH as u32 + 1;
+        let rows = glyph_count.div_ceil(ATLAS_COLUMNS);
+
+        assert_eq!(atlas.width, ATLAS_COLUMNS * GLYPH_WIDTH as u32);
+        assert_eq!(atlas.height, rows * GLYPH_HEIGHT as u32);
+        assert_eq!(atlas.pixels.len(), (atlas.width * atlas.height) as usize);
+    }
+
+    #[test]
+    fn broken_atlas_is_an_error() {
+        assert!(matches!(
+            parse_pgm(b"P5 8 13 255 "),
+            Err(GraphicsError::Internal(_))
+        ));
+        assert!(matches!(
+            parse_pgm(b"P6 1 1 255 rgb"),
+            Err(GraphicsError::Internal(_))
+        ));
+        assert!(matches!(
+            parse_pgm(b"P5 8"),
+            Err(GraphicsError::Internal(_))
+        ));
+    }
+
+    #[test]
+    fn glyphs_are_placed_in_screen_space() {
+        let mut text = text("AB");
+        text.position = [400.0, 300.0];
+        text.scale = 2.0;
+        let mut vertices = vec![];
+        layout_text(&text, EXTENT, &mut vertices);
+
+        assert_eq!(vertices.len(), 2 * VERTICES_PER_GLYPH);
+        // top left corner of 'A' sits at the centre of the screen
+        assert_eq!(vertices[0].pos, [0.0, 0.0]);
+        // bottom right corner of 'B' is two scaled glyphs to the right and one down
+        let corner = vertices[VERTICES_PER_GLYPH + 2].pos;
+        assert!((corner[0] - 32.0 / 400.0).abs() < 1e-6);
+        assert!((corner[1] - 26.0 / 300.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn spaces_and_new_lines_only_move_the_pen() {
+        let mut vertices = vec![];
+        layout_text(&text("a b\nc"), EXTENT, &mut vertices);
+
+        assert_eq!(vertices.len(), 3 * VERTICES_PER_GLYPH);
+        assert_eq!(vertices[VERTICES_PER_GLYPH].pos[0], 16.0 / 400.0 - 1.0);
+        assert_eq!(vertices[2 * VERTICES_PER_GLYPH].pos[1], 13.0 / 300.0 - 1.0);
+        assert_eq!(text_size("a b\nc", 1.0), [24.0, 26.0]);
+    }
+
+    #[test]
+    fn unknown_characters_use_the_fallback_glyph() {
+        let mut unknown = vec![];
+        let mut fallback = vec![];
+        layout_text(&text("é"), EXTENT, &mut unknown);
+        layout_text(&text("?"), EXTENT, &mut fallback);
+
+        assert_eq!(unknown[0].tex_coord, fallback[0].tex_coord);
+    }
+
+    #[test]
+    fn glyphs_beyond_capacity_are_dropped() {
+        let long = "x".repeat(MAX_GLYPHS + 10);
+        let mut vertices = vec![];
+        layout_text(&text(&long), EXTENT, &mut vertices);
+
+        assert_eq!(vertices.len(), MAX_GLYPHS * VERTICES_PER_GLYPH);
+    }
+}
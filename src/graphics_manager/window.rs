@@ -1,14 +1,32 @@
 use winit::event_loop::EventLoop;
+use winit::window::Fullscreen;
+
+use super::error::GraphicsError;
+use crate::config::WindowConfig;
 
 pub fn init_window(
     event_loop: &EventLoop<()>,
     title: &str,
-    width: u32,
-    height: u32,
-) -> winit::window::Window {
+    config: &WindowConfig,
+) -> Result<winit::window::Window, GraphicsError> {
+    let fullscreen = if config.fullscreen {
+        Some(Fullscreen::Borderless(event_loop.primary_monitor()))
+    } else {
+        None
+    };
+    winit::window::WindowBuilder::new()
+        .with_title(title)
+        .with_inner_size(winit::dpi::LogicalSize::new(config.width, config.height))
+        .with_fullscreen(fullscreen)
+        .build(event_loop)
+        .map_err(|error| GraphicsError::Window(format!("could not be created: {}", error)))
+}
+
+/// Window that is never shown, for a surface to ask the devices about.
+pub fn init_hidden_window(event_loop: &EventLoop<()>, title: &str) -> Result<winit::window::Window, GraphicsError> {
     winit::window::WindowBuilder::new()
         .with_title(title)
-        .with_inner_size(winit::dpi::LogicalSize::new(width, height))
+        .with_visible(false)
         .build(event_loop)
-        .expect("Failed to create window.")
+        .map_err(|error| GraphicsError::Window(format!("could not be created: {}", error)))
 }
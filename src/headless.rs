@@ -0,0 +1,178 @@
+use std::fmt;
+
+use crate::game::{Game, GamePhase};
+use crate::score::Score;
+
+/// Simulation steps run in headless mode unless told otherwise, ten minutes of play
+pub const DEFAULT_HEADLESS_TICKS: u64 = 120 * 60 * 10;
+
+/// How a game stood when a headless run stopped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    pub ticks: u64,
+    pub seconds: f32,
+    /// Matches each side won during the run
+    pub matches: Score,
+    /// Score of the match that was going on at the end
+    pub score: Score,
+    pub phase: GamePhase,
+    /// Most paddle hits in one rally
+    pub longest_rally: u32,
+    pub ball_position: [f32; 2],
+    pub ball_velocity: [f32; 2],
+    pub paddles: [f32; 2],
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Steps: {} ({:.1} s)", self.ticks, self.seconds)?;
+        writeln!(
+            f,
+            "Matches won: left {}, right {}",
+            self.matches.left, self.matches.right
+        )?;
+        writeln!(
+            f,
+            "Score: {} - {} ({:?})",
+            self.score.left, self.score.right, self.phase
+        )?;
+        writeln!(
+            f,
+            "Ball: position ({:.3}, {:.3}) velocity ({:.3}, {:.3})",
+            self.ball_position[0],
+            self.ball_position[1],
+            self.ball_velocity[0],
+            self.ball_velocity[1]
+        )?;
+        writeln!(
+            f,
+            "Paddles: left {:.3}, right {:.3}",
+            self.paddles[0], self.paddles[1]
+        )?;
+        write!(f, "Longest rally: {} hits", self.longest_rally)
+    }
+}
+
+/// Plays `game` without a window for up to `ticks` steps of `step` seconds.
+///
+/// A replay is followed until it runs out. Otherwise the paddles are left to
+/// the computer players of the game, every match is served as soon as it can
+/// be and a new one starts when it ends.
+pub fn run(game: &mut Game, ticks: u64, step: f32) -> Summary {
+    let mut matches = Score::default();
+    let mut longest_rally = 0;
+    while game.tick() < ticks {
+        game.play_replay_events();
+        if game.is_replay_finished() {
+            break;
+        }
+        match game.phase {
+            GamePhase::Playing => {
+                game.simulate(step);
+                longest_rally = longest_rally.max(game.scene.rally_length());
+                if game.phase == GamePhase::End {
+                    if let Some(winner) = game.scene.winner() {
+                        matches.point_to(winner);
+                    }
+                }
+            }
+            // Only the replay could get the game going again, and it has nothing left for now
+            _ if game.is_replay() => break,
+            GamePhase::End => game.restart(),
+            _ => game.kickoff(),
+        }
+    }
+
+    let scene = &game.scene;
+    Summary {
+        ticks: game.tick(),
+        seconds: game.tick() as f32 * step,
+        matches,
+        score: scene.score(),
+        phase: game.phase,
+        longest_rally,
+        ball_position: [scene.ball.position.x, scene.ball.position.y],
+        ball_velocity: [scene.ball.velocity.x, scene.ball.velocity.y],
+        paddles: [scene.left_paddle.position.y, scene.right_paddle.position.y],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{CpuPlayer, Difficulty};
+    use crate::paddle::PaddleMotion;
+    use crate::rally::RallySpeed;
+    use crate::replay::{Recorder, Replay, ReplayPlayer};
+    use crate::scene::Court;
+    use crate::score::Side;
+    use crate::settings::{Opponent, Settings};
+
+    fn cpu_game(seed: u64) -> Game {
+        let settings = Settings {
+            target_score: 3,
+            opponent: Opponent::Cpu(Difficulty::Hard),
+            ..Settings::default()
+        };
+        Game::new(seed, settings, GamePhase::Start)
+    }
+
+    #[test]
+    fn matches_are_played_one_after_the_other() {
+        // Nobody defends the left side, so points come quickly
+        let mut game = cpu_game(5);
+        let summary = run(&mut game, 120 * 60 * 2, 1.0 / 120.0);
+
+        assert_eq!(summary.ticks, 120 * 60 * 2);
+        assert!(summary.matches.right >= 2);
+        assert_eq!(summary.matches.left, 0);
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_run() {
+        let game = || {
+            let mut game = cpu_game(9);
+            game.add_cpu_player(CpuPlayer::new(Side::Left, Difficulty::Easy, 10));
+            game
+        };
+        let first = run(&mut game(), 10_000, 1.0 / 120.0);
+        let second = run(&mut game(), 10_000, 1.0 / 120.0);
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn recorded_run_replays_the_same() {
+        let path = std::env::temp_dir().join(format!("pong-rust-headless-{}", std::process::id()));
+        let mut recorded = cpu_game(4);
+        recorded.add_cpu_player(CpuPlayer::new(Side::Left, Difficulty::Normal, 5));
+        let header = Replay {
+            seed: 4,
+            simulation_rate: 120.0,
+            settings: recorded.settings,
+            court: Court::default(),
+            left_paddle: PaddleMotion::default(),
+            right_paddle: PaddleMotion::default(),
+            rally: RallySpeed::default(),
+            events: vec![],
+        };
+        let recorder = Recorder::create(&path, &header).unwrap();
+        recorded.record(recorder);
+        let original = run(&mut recorded, 5_000, 1.0 / 120.0);
+        recorded.stop_recording();
+
+        let replay = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let replay = replay.unwrap();
+        let mut replayed = Game::new(replay.seed, replay.settings, GamePhase::Start);
+        replayed.replay(ReplayPlayer::new(&replay));
+        let summary = run(&mut replayed, u64::MAX, 1.0 / replay.simulation_rate);
+
+        assert!(replayed.is_replay_finished());
+        assert_eq!(summary.ticks, original.ticks);
+        assert_eq!(summary.score, original.score);
+        assert_eq!(summary.ball_position, original.ball_position);
+        assert_eq!(summary.paddles, original.paddles);
+        assert_eq!(summary.longest_rally, original.longest_rally);
+    }
+}
@@ -0,0 +1,286 @@
+use crate::game::GamePhase;
+use crate::graphics_manager::text::{text_size, Text, GLYPH_HEIGHT};
+use crate::input::{InputAction, KeyBindings};
+use crate::menu::Menu;
+use crate::scene::Scene;
+use crate::score::Side;
+use crate::settings::Settings;
+
+const SCORE_SCALE: f32 = 4.0;
+const BANNER_SCALE: f32 = 3.0;
+const HINT_SCALE: f32 = 2.0;
+const MENU_SCALE: f32 = 2.0;
+/// Lines of menu text between the title and the first item
+const MENU_TITLE_GAP: f32 = 2.0;
+/// Distance in pixels between the top of the window and the scores
+const MARGIN: f32 = 24.0;
+
+const SCORE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.8];
+const BANNER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const HINT_COLOR: [f32; 4] = [0.7, 0.7, 0.7, 1.0];
+const SELECTED_COLOR: [f32; 4] = [1.0, 0.9, 0.2, 1.0];
+
+/// Texts drawn over the court for the current `phase`, laid out for a window of `screen_size` pixels.
+pub fn overlay(
+    phase: GamePhase,
+    scene: &Scene,
+    menu: &Menu,
+    settings: &Settings,
+    bindings: &KeyBindings,
+    screen_size: [f32; 2],
+) -> Vec<Text> {
+    let [width, height] = screen_size;
+    let score = scene.score();
+
+    // The main menu and the settings hide the court, so there is no score to show
+    if phase == GamePhase::Menu {
+        return menu_texts(menu, settings, bindings, screen_size);
+    }
+    let serve_key = bindings
+        .keys(InputAction::Serve)
+        .first()
+        .map_or("Serve", |key| key.name());
+
+    // Each score sits above the half of the court its player defends
+    let mut texts = vec![
+        centered(
+            &score.left.to_string(),
+            [width * 0.25, MARGIN],
+            SCORE_SCALE,
+            SCORE_COLOR,
+        ),
+        centered(
+            &score.right.to_string(),
+            [width * 0.75, MARGIN],
+            SCORE_SCALE,
+            SCORE_COLOR,
+        ),
+    ];
+
+    match phase {
+        GamePhase::Start => {
+            texts.push(banner(
+                &format!("Press {} to start", serve_key),
+                screen_size,
+            ));
+        }
+        GamePhase::Playing => {
+            // Shown between the scores once the ball has been returned
+            if scene.rally_length() > 0 {
+                texts.push(centered(
+                    &format!(
+                        "Rally {}, speed {:.1}",
+                        scene.rally_length(),
+                        scene.rally_speed()
+                    ),
+                    [width / 2.0, MARGIN],
+                    HINT_SCALE,
+                    HINT_COLOR,
+                ));
+            }
+        }
+        GamePhase::Menu => {}
+        GamePhase::Paused => texts.extend(menu_texts(menu, settings, bindings, screen_size)),
+        GamePhase::End => {
+            let message = match scene.winner() {
+                Some(Side::Left) => "Left player wins",
+                Some(Side::Right) => "Right player wins",
+                None => "Game over",
+            };
+            texts.push(banner(message, screen_size));
+            // Hint goes right under the banner, one line of banner text apart
+            texts.push(centered(
+                &format!("Press {} to continue", serve_key),
+                [width / 2.0, height / 2.0 + BANNER_SCALE * GLYPH_HEIGHT],
+                HINT_SCALE,
+                HINT_COLOR,
+            ));
+        }
+    }
+
+    texts
+}
+
+/// Title and items of the current menu screen, centred on the screen with the selected item highlighted.
+fn menu_texts(
+    menu: &Menu,
+    settings: &Settings,
+    bindings: &KeyBindings,
+    screen_size: [f32; 2],
+) -> Vec<Text> {
+    let [width, height] = screen_size;
+    let labels = menu.labels(settings, bindings);
+    let line_height = GLYPH_HEIGHT * MENU_SCALE * 1.5;
+    let block_height = line_height * (labels.len() as f32 + MENU_TITLE_GAP);
+    let top = (height - block_height) / 2.0;
+
+    let mut texts = vec![centered(
+        menu.screen().title(),
+        [width / 2.0, top - GLYPH_HEIGHT * BANNER_SCALE],
+        BANNER_SCALE,
+        BANNER_COLOR,
+    )];
+    for (index, label) in labels.iter().enumerate() {
+        let (content, color) = if index == menu.selected() {
+            (format!("> {} <", label), SELECTED_COLOR)
+        } else {
+            (label.clone(), HINT_COLOR)
+        };
+        let y = top + line_height * (index as f32 + MENU_TITLE_GAP);
+        texts.push(centered(&content, [width / 2.0, y], MENU_SCALE, color));
+    }
+
+    texts
+}
+
+/// Large text centred on the screen.
+fn banner(content: &str, screen_size: [f32; 2]) -> Text {
+    let [width, height] = screen_size;
+    let [_, text_height] = text_size(content, BANNER_SCALE);
+    centered(
+        content,
+        [width / 2.0, (height - text_height) / 2.0],
+        BANNER_SCALE,
+        BANNER_COLOR,
+    )
+}
+
+/// Text horizontally centred on `anchor`, with its top at the anchor's height.
+fn centered(content: &str, anchor: [f32; 2], scale: f32, color: [f32; 4]) -> Text {
+    let [text_width, _] = text_size(content, scale);
+    Text {
+        content: content.to_string(),
+        position: [anchor[0] - text_width / 2.0, anchor[1]],
+        scale,
+        color,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::menu::MenuScreen;
+    use crate::scene::Action;
+    use cgmath::{Vector2, Vector3};
+
+    const SCREEN: [f32; 2] = [800.0, 600.0];
+
+    fn overlay_for(phase: GamePhase, scene: &Scene) -> Vec<Text> {
+        overlay(
+            phase,
+            scene,
+            &Menu::new(),
+            &Settings::default(),
+            &KeyBindings::default(),
+            SCREEN,
+        )
+    }
+
+    fn contents(texts: &[Text]) -> Vec<&str> {
+        texts.iter().map(|text| text.content.as_str()).collect()
+    }
+
+    #[test]
+    fn scores_sit_above_their_half() {
+        let scene = Scene::new(0);
+        let texts = overlay_for(GamePhase::Playing, &scene);
+
+        assert_eq!(contents(&texts), vec!["0", "0"]);
+        assert!(texts[0].position[0] < SCREEN[0] / 2.0);
+        assert!(texts[1].position[0] > SCREEN[0] / 2.0);
+    }
+
+    #[test]
+    fn rally_shows_between_the_scores() {
+        let mut scene = Scene::new(0);
+        // Ball heads straight at the right paddle, which sends it back
+        scene.ball.position = Vector3::new(3.0, 0.0, 0.0);
+        scene.ball.velocity = Vector2::new(4.0, 0.0);
+        scene.update(0.25);
+        let texts = overlay_for(GamePhase::Playing, &scene);
+
+        assert_eq!(contents(&texts), vec!["0", "0", "Rally 1, speed 4.0"]);
+        assert_eq!(
+            texts[2].position[0] + text_size(&texts[2].content, HINT_SCALE)[0] / 2.0,
+            SCREEN[0] / 2.0
+        );
+    }
+
+    #[test]
+    fn start_phase_invites_to_play() {
+        let scene = Scene::new(0);
+        let texts = overlay_for(GamePhase::Start, &scene);
+
+        assert!(contents(&texts).contains(&"Press Space to start"));
+    }
+
+    #[test]
+    fn hints_name_the_serve_key() {
+        let scene = Scene::new(0);
+        let mut bindings = KeyBindings::default();
+        bindings.rebind(InputAction::Serve, crate::input::Key::Return);
+        let texts = overlay(
+            GamePhase::Start,
+            &scene,
+            &Menu::new(),
+            &Settings::default(),
+            &bindings,
+            SCREEN,
+        );
+
+        assert!(contents(&texts).contains(&"Press Enter to start"));
+    }
+
+    #[test]
+    fn end_phase_names_the_winner() {
+        let mut scene = Scene::new(0);
+        scene.rules.target_score = 1;
+        // Ball flies past the left paddle, so the right player scores
+        scene.ball.position = Vector3::new(-3.0, 2.5, 0.0);
+        scene.ball.velocity = Vector2::new(-4.0, 0.0);
+        scene.update(0.5);
+        scene.handle_action(Action::GameOver);
+        let texts = overlay_for(GamePhase::End, &scene);
+
+        assert!(contents(&texts).contains(&"Right player wins"));
+        assert_eq!(texts[1].content, "1");
+    }
+
+    #[test]
+    fn menu_highlights_the_selected_item() {
+        let scene = Scene::new(0);
+        let texts = overlay_for(GamePhase::Menu, &scene);
+
+        assert_eq!(
+            contents(&texts),
+            vec!["PONG", "> Play <", "Settings", "Quit"]
+        );
+        assert_eq!(texts[1].color, SELECTED_COLOR);
+    }
+
+    #[test]
+    fn pause_menu_keeps_the_score_visible() {
+        let scene = Scene::new(0);
+        let mut menu = Menu::new();
+        menu.open(MenuScreen::Pause);
+        let texts = overlay(
+            GamePhase::Paused,
+            &scene,
+            &menu,
+            &Settings::default(),
+            &KeyBindings::default(),
+            SCREEN,
+        );
+
+        assert_eq!(contents(&texts)[..3], ["0", "0", "Paused"]);
+    }
+
+    #[test]
+    fn banner_is_centred() {
+        let text = banner("Pong", SCREEN);
+        let [text_width, text_height] = text_size("Pong", BANNER_SCALE);
+
+        assert_eq!(text.position[0] + text_width / 2.0, SCREEN[0] / 2.0);
+        assert_eq!(text.position[1] + text_height / 2.0, SCREEN[1] / 2.0);
+    }
+}
@@ -0,0 +1,361 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::scene::Direction;
+use crate::score::Side;
+
+pub mod gamepad;
+mod key;
+pub mod mouse;
+
+pub use key::Key;
+
+/// Something a player asks for during a match, whatever key they press for it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputAction {
+    LeftPaddleUp,
+    LeftPaddleDown,
+    RightPaddleUp,
+    RightPaddleDown,
+    /// Starts the match, pauses it while playing, and clears the score once it is over
+    Serve,
+    Pause,
+    /// Pauses the match while playing, goes back to the main menu otherwise, and ends a replay
+    Back,
+}
+
+impl InputAction {
+    /// Actions in the order the controls screen lists them.
+    pub const ALL: [InputAction; 7] = [
+        InputAction::LeftPaddleUp,
+        InputAction::LeftPaddleDown,
+        InputAction::RightPaddleUp,
+        InputAction::RightPaddleDown,
+        InputAction::Serve,
+        InputAction::Pause,
+        InputAction::Back,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            InputAction::LeftPaddleUp => "Left paddle up",
+            InputAction::LeftPaddleDown => "Left paddle down",
+            InputAction::RightPaddleUp => "Right paddle up",
+            InputAction::RightPaddleDown => "Right paddle down",
+            InputAction::Serve => "Serve",
+            InputAction::Pause => "Pause",
+            InputAction::Back => "Back",
+        }
+    }
+
+    /// The paddle this action steers and where to, `None` for the other actions.
+    pub fn paddle(self) -> Option<(Side, Direction)> {
+        match self {
+            InputAction::LeftPaddleUp => Some((Side::Left, Direction::Up)),
+            InputAction::LeftPaddleDown => Some((Side::Left, Direction::Down)),
+            InputAction::RightPaddleUp => Some((Side::Right, Direction::Up)),
+            InputAction::RightPaddleDown => Some((Side::Right, Direction::Down)),
+            InputAction::Serve | InputAction::Pause | InputAction::Back => None,
+        }
+    }
+}
+
+/// Keys bound to each `InputAction`, read from the `[controls]` section of the configuration.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyBindings {
+    left_paddle_up: Vec<Key>,
+    left_paddle_down: Vec<Key>,
+    right_paddle_up: Vec<Key>,
+    right_paddle_down: Vec<Key>,
+    serve: Vec<Key>,
+    pause: Vec<Key>,
+    back: Vec<Key>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            left_paddle_up: vec![Key::W],
+            left_paddle_down: vec![Key::S],
+            right_paddle_up: vec![Key::I],
+            right_paddle_down: vec![Key::K],
+            serve: vec![Key::Space],
+            pause: vec![Key::P],
+            back: vec![Key::Escape],
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn keys(&self, action: InputAction) -> &[Key] {
+        match action {
+            InputAction::LeftPaddleUp => &self.left_paddle_up,
+            InputAction::LeftPaddleDown => &self.left_paddle_down,
+            InputAction::RightPaddleUp => &self.right_paddle_up,
+            InputAction::RightPaddleDown => &self.right_paddle_down,
+            InputAction::Serve => &self.serve,
+            InputAction::Pause => &self.pause,
+            InputAction::Back => &self.back,
+        }
+    }
+
+    fn keys_mut(&mut self, action: InputAction) -> &mut Vec<Key> {
+        match action {
+            InputAction::LeftPaddleUp => &mut self.left_paddle_up,
+            InputAction::LeftPaddleDown => &mut self.left_paddle_down,
+            InputAction::RightPaddleUp => &mut self.right_paddle_up,
+            InputAction::RightPaddleDown => &mut self.right_paddle_down,
+            InputAction::Serve => &mut self.serve,
+            InputAction::Pause => &mut self.pause,
+            InputAction::Back => &mut self.back,
+        }
+    }
+
+    /// Binds `key` alone to `action`, the keys it had before are unbound.
+    pub fn rebind(&mut self, action: InputAction, key: Key) {
+        *self.keys_mut(action) = vec![key];
+    }
+
+    /// Binds `key` alone to `action` like `rebind`, and hands the keys `action`
+    /// had to the other action `key` was bound to, so the two trade places
+    /// instead of conflicting.
+    pub fn rebind_swapping(&mut self, action: InputAction, key: Key) {
+        let previous = std::mem::replace(self.keys_mut(action), vec![key]);
+        for other in InputAction::ALL.iter().filter(|other| **other != action) {
+            let keys = self.keys_mut(*other);
+            if let Some(index) = keys.iter().position(|bound| *bound == key) {
+                keys.splice(index..=index, previous.iter().copied());
+            }
+        }
+    }
+
+    /// The action `key` is bound to, the first in `InputAction::ALL` when it has several.
+    pub fn action_of(&self, key: Key) -> Option<InputAction> {
+        InputAction::ALL
+            .iter()
+            .copied()
+            .find(|action| self.keys(*action).contains(&key))
+    }
+
+    /// Keys bound to more than one action, which only ever do the first of them.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = vec![];
+        for (index, action) in InputAction::ALL.iter().enumerate() {
+            for key in self.keys(*action) {
+                for other in &InputAction::ALL[index + 1..] {
+                    if self.keys(*other).contains(key) {
+                        conflicts.push(Conflict {
+                            key: *key,
+                            actions: [*action, *other],
+                        });
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Whether one of the keys of `action` is also bound to another action.
+    pub fn has_conflict(&self, action: InputAction) -> bool {
+        self.conflicts()
+            .iter()
+            .any(|conflict| conflict.actions.contains(&action))
+    }
+}
+
+/// Keys held down to steer one paddle, in the order they were pressed.
+///
+/// The paddle goes where the last key pressed that is still held says, so
+/// releasing it hands the paddle back to the key held before it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HeldKeys {
+    held: Vec<(Key, Direction)>,
+}
+
+impl HeldKeys {
+    /// Holds `key`, which steers towards `direction`. Repeated presses of a held key change nothing.
+    pub fn press(&mut self, key: Key, direction: Direction) {
+        if self.held.iter().all(|(held, _)| *held != key) {
+            self.held.push((key, direction));
+        }
+    }
+
+    pub fn release(&mut self, key: Key) {
+        self.held.retain(|(held, _)| *held != key);
+    }
+
+    /// Forgets every key, for when their releases won't be seen.
+    pub fn clear(&mut self) {
+        self.held.clear();
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.held
+            .last()
+            .map_or(Direction::Stop, |(_, direction)| *direction)
+    }
+}
+
+/// A key bound to two actions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub key: Key,
+    pub actions: [InputAction; 2],
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} is bound to both {} and {}",
+            self.key,
+            self.actions[0].name().to_lowercase(),
+            self.actions[1].name().to_lowercase()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_bindings_have_no_conflicts() {
+        let bindings = KeyBindings::default();
+
+        assert_eq!(bindings.conflicts(), vec![]);
+        assert_eq!(bindings.action_of(Key::W), Some(InputAction::LeftPaddleUp));
+        assert_eq!(bindings.action_of(Key::Escape), Some(InputAction::Back));
+        assert_eq!(bindings.action_of(Key::Q), None);
+    }
+
+    #[test]
+    fn rebinding_replaces_the_keys_of_an_action() {
+        let mut bindings = KeyBindings::default();
+        bindings.rebind(InputAction::LeftPaddleUp, Key::Z);
+
+        assert_eq!(bindings.keys(InputAction::LeftPaddleUp), [Key::Z]);
+        assert_eq!(bindings.action_of(Key::Z), Some(InputAction::LeftPaddleUp));
+        assert_eq!(bindings.action_of(Key::W), None);
+    }
+
+    #[test]
+    fn rebinding_a_bound_key_swaps_the_two_actions() {
+        let mut bindings = KeyBindings {
+            right_paddle_up: vec![Key::I, Key::Up],
+            ..KeyBindings::default()
+        };
+        bindings.rebind_swapping(InputAction::LeftPaddleUp, Key::Up);
+
+        assert_eq!(bindings.keys(InputAction::LeftPaddleUp), [Key::Up]);
+        assert_eq!(bindings.keys(InputAction::RightPaddleUp), [Key::I, Key::W]);
+        assert_eq!(bindings.conflicts(), vec![]);
+
+        bindings.rebind_swapping(InputAction::Serve, Key::Q);
+        assert_eq!(bindings.keys(InputAction::Serve), [Key::Q]);
+        assert_eq!(bindings.action_of(Key::Space), None);
+    }
+
+    #[test]
+    fn keys_bound_twice_are_conflicts() {
+        let mut bindings = KeyBindings::default();
+        bindings.rebind(InputAction::RightPaddleUp, Key::W);
+
+        assert_eq!(
+            bindings.conflicts(),
+            vec![Conflict {
+                key: Key::W,
+                actions: [InputAction::LeftPaddleUp, InputAction::RightPaddleUp],
+            }]
+        );
+        assert!(bindings.has_conflict(InputAction::RightPaddleUp));
+        assert!(!bindings.has_conflict(InputAction::Serve));
+        assert_eq!(
+            bindings.conflicts()[0].to_string(),
+            "W is bound to both left paddle up and right paddle up"
+        );
+    }
+
+    /// Direction after each `(key, pressed)` event, starting with nothing held.
+    fn directions(events: &[(Key, bool)]) -> Vec<Direction> {
+        let bindings = KeyBindings {
+            left_paddle_up: vec![Key::W, Key::Up],
+            ..KeyBindings::default()
+        };
+        let mut held = HeldKeys::default();
+        events
+            .iter()
+            .map(|(key, pressed)| {
+                let (_, direction) = bindings.action_of(*key).unwrap().paddle().unwrap();
+                if *pressed {
+                    held.press(*key, direction);
+                } else {
+                    held.release(*key);
+                }
+                held.direction()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn last_pressed_key_wins() {
+        use Direction::*;
+
+        assert_eq!(
+            directions(&[(Key::S, true), (Key::W, true), (Key::W, false)]),
+            [Down, Up, Down]
+        );
+        assert_eq!(
+            directions(&[(Key::W, true), (Key::S, true), (Key::S, false)]),
+            [Up, Down, Up]
+        );
+        assert_eq!(
+            directions(&[
+                (Key::S, true),
+                (Key::W, true),
+                (Key::S, false),
+                (Key::W, false)
+            ]),
+            [Down, Up, Up, Stop]
+        );
+    }
+
+    #[test]
+    fn direction_lasts_while_one_of_its_keys_is_held() {
+        use Direction::*;
+
+        assert_eq!(
+            directions(&[
+                (Key::W, true),
+                (Key::Up, true),
+                (Key::W, false),
+                (Key::Up, false)
+            ]),
+            [Up, Up, Up, Stop]
+        );
+        // Held keys repeat their presses, which must not bring them back on top
+        assert_eq!(
+            directions(&[
+                (Key::W, true),
+                (Key::S, true),
+                (Key::W, true),
+                (Key::S, false)
+            ]),
+            [Up, Down, Down, Up]
+        );
+        assert_eq!(directions(&[(Key::S, false)]), [Stop]);
+    }
+
+    #[test]
+    fn keys_are_named_whatever_the_case() {
+        assert_eq!(Key::from_name("space"), Some(Key::Space));
+        assert_eq!(Key::from_name("Enter"), Some(Key::Return));
+        assert_eq!(Key::from_name("1"), Some(Key::Key1));
+        assert_eq!(Key::from_name("Any"), None);
+        for key in Key::ALL {
+            assert_eq!(Key::from_name(key.name()), Some(*key));
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use crate::config::{DeviceChoice, GamepadConfig};
+use crate::scene::{Action, Direction};
+use crate::score::Side;
+
+#[cfg(target_os = "linux")]
+mod linux;
+
+/// Number a backend gives a gamepad, which stays the same while it is plugged in.
+pub type GamepadId = usize;
+
+/// A vertical axis of a gamepad.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GamepadAxis {
+    /// Analog, the paddle goes as fast as the stick is pushed
+    Stick,
+    /// Digital, the paddle goes at full speed or not at all
+    DPad,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GamepadEvent {
+    Connected {
+        id: GamepadId,
+        name: String,
+    },
+    Disconnected {
+        id: GamepadId,
+    },
+    /// Position of an axis, from -1 at the top to 1 at the bottom
+    Axis {
+        id: GamepadId,
+        axis: GamepadAxis,
+        value: f32,
+    },
+}
+
+/// Where the events of the gamepads come from.
+pub trait GamepadBackend {
+    /// Events that happened since the last call, in order.
+    fn poll(&mut self) -> Vec<GamepadEvent>;
+}
+
+/// Backend for when gamepads are turned off, or the system has no support for them.
+pub struct NoGamepads;
+
+impl GamepadBackend for NoGamepads {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        vec![]
+    }
+}
+
+/// The gamepads of the system, unless `config` turns them off.
+pub fn system_backend(config: &GamepadConfig) -> Box<dyn GamepadBackend> {
+    if config.enabled {
+        system_gamepads(config)
+    } else {
+        Box::new(NoGamepads)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn system_gamepads(config: &GamepadConfig) -> Box<dyn GamepadBackend> {
+    Box::new(linux::Joysticks::start(config.stick_axis, config.dpad_axis))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn system_gamepads(_config: &GamepadConfig) -> Box<dyn GamepadBackend> {
+    log::warn!("Gamepads are only supported on Linux so far");
+    Box::new(NoGamepads)
+}
+
+struct Gamepad {
+    id: GamepadId,
+    name: String,
+    side: Option<Side>,
+    stick: f32,
+    dpad: f32,
+}
+
+impl Gamepad {
+    /// Speed the gamepad asks of its paddle, from -1 upwards to 1 downwards.
+    fn speed(&self, dead_zone: f32) -> f32 {
+        if self.dpad.abs() >= 0.5 {
+            self.dpad.signum()
+        } else if self.stick.abs() <= dead_zone {
+            0.0
+        } else {
+            // Leaving the dead zone starts from a standstill rather than with a jump
+            self.stick.signum() * (self.stick.abs().min(1.0) - dead_zone) / (1.0 - dead_zone)
+        }
+    }
+}
+
+/// The gamepads plugged in, which paddle each of them steers and how fast.
+///
+/// A gamepad takes the first paddle nobody steers yet, unless the configuration
+/// chooses another gamepad for it, and hands it over when it is unplugged.
+pub struct Gamepads {
+    backend: Box<dyn GamepadBackend>,
+    dead_zone: f32,
+    left_choice: Option<DeviceChoice>,
+    right_choice: Option<DeviceChoice>,
+    pads: Vec<Gamepad>,
+    /// Last action sent to each paddle, so that an unchanged speed isn't sent again
+    left_sent: Option<Action>,
+    right_sent: Option<Action>,
+}
+
+impl Gamepads {
+    pub fn new(backend: Box<dyn GamepadBackend>, config: &GamepadConfig) -> Self {
+        Self {
+            backend,
+            dead_zone: config.dead_zone,
+            left_choice: config.left.clone(),
+            right_choice: config.right.clone(),
+            pads: vec![],
+            left_sent: None,
+            right_sent: None,
+        }
+    }
+
+    /// Paddle steered by the gamepad `id`, if it is plugged in and steers one.
+    pub fn side_of(&self, id: GamepadId) -> Option<Side> {
+        self.pads
+            .iter()
+            .find(|pad| pad.id == id)
+            .and_then(|pad| pad.side)
+    }
+
+    /// Reads what the gamepads did since the last call, and returns the actions
+    /// for the paddles whose speed changed.
+    pub fn poll(&mut self) -> Vec<Action> {
+        for event in self.backend.poll() {
+            self.handle_event(event);
+        }
+
+        let mut actions = vec![];
+        for side in [Side::Left, Side::Right].iter().copied() {
+            let dead_zone = self.dead_zone;
+            let wanted = self
+                .pads
+                .iter()
+                .find(|pad| pad.side == Some(side))
+                .map(|pad| Action::steer(side, pad.speed(dead_zone)));
+            let sent = match side {
+                Side::Left => &mut self.left_sent,
+                Side::Right => &mut self.right_sent,
+            };
+            if wanted != *sent {
+                match wanted {
+                    Some(action) => actions.push(action),
+                    // The gamepad was unplugged, its paddle must not keep going
+                    None => actions.push(Action::paddle(side, Direction::Stop)),
+                }
+                *sent = wanted;
+            }
+        }
+        actions
+    }
+
+    /// The paddles were stopped behind the back of the gamepads, the next poll
+    /// sends them their speed again.
+    pub fn forget_sent(&mut self) {
+        self.left_sent = None;
+        self.right_sent = None;
+    }
+
+    /// Hands the left paddle to the gamepad steering the right one, and the other way round.
+    pub fn swap_sides(&mut self) {
+        for pad in &mut self.pads {
+            pad.side = pad.side.map(Side::opponent);
+        }
+        self.assign_free_sides();
+    }
+
+    fn handle_event(&mut self, event: GamepadEvent) {
+        match event {
+            GamepadEvent::Connected { id, name } => {
+                log::info!("Gamepad {} connected: {}", id, name);
+                self.pads.retain(|pad| pad.id != id);
+                self.pads.push(Gamepad {
+                    id,
+                    name,
+                    side: None,
+                    stick: 0.0,
+                    dpad: 0.0,
+                });
+                self.assign_free_sides();
+            }
+            GamepadEvent::Disconnected { id } => {
+                log::info!("Gamepad {} disconnected", id);
+                self.pads.retain(|pad| pad.id != id);
+                self.assign_free_sides();
+            }
+            GamepadEvent::Axis { id, axis, value } => {
+                if let Some(pad) = self.pads.iter_mut().find(|pad| pad.id == id) {
+                    match axis {
+                        GamepadAxis::Stick => pad.stick = value,
+                        GamepadAxis::DPad => pad.dpad = value,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Gives each paddle nobody steers to the first gamepad without one that may steer it.
+    fn assign_free_sides(&mut self) {
+        for side in [Side::Left, Side::Right].iter().copied() {
+            if self.pads.iter().any(|pad| pad.side == Some(side)) {
+                continue;
+            }
+            let choice = match side {
+                Side::Left => &self.left_choice,
+                Side::Right => &self.right_choice,
+            };
+            let free = self.pads.iter_mut().find(|pad| {
+                pad.side.is_none()
+                    && choice
+                        .iter()
+                        .all(|choice| choice.matches(pad.id, &pad.name))
+            });
+            if let Some(pad) = free {
+                log::info!("Gamepad {} steers the {:?} paddle", pad.id, side);
+                pad.side = Some(side);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Fake device handing out one list of scripted events per poll.
+    struct ScriptedGamepads {
+        polls: VecDeque<Vec<GamepadEvent>>,
+    }
+
+    fn gamepads(config: &GamepadConfig, polls: Vec<Vec<GamepadEvent>>) -> Gamepads {
+        let backend = ScriptedGamepads {
+            polls: polls.into(),
+        };
+        Gamepads::new(Box::new(backend), config)
+    }
+
+    impl GamepadBackend for ScriptedGamepads {
+        fn poll(&mut self) -> Vec<GamepadEvent> {
+            self.polls.pop_front().unwrap_or_default()
+        }
+    }
+
+    fn connected(id: GamepadId, name: &str) -> GamepadEvent {
+        GamepadEvent::Connected {
+            id,
+            name: name.to_string(),
+        }
+    }
+
+    fn axis(id: GamepadId, axis: GamepadAxis, value: f32) -> GamepadEvent {
+        GamepadEvent::Axis { id, axis, value }
+    }
+
+    #[test]
+    fn stick_gives_proportional_speed() {
+        let mut gamepads = gamepads(
+            &GamepadConfig::default(),
+            vec![
+                vec![connected(0, "Pad"), axis(0, GamepadAxis::Stick, 0.6)],
+                vec![axis(0, GamepadAxis::Stick, 0.1)],
+                vec![axis(0, GamepadAxis::Stick, -1.0)],
+            ],
+        );
+
+        // Halfway between the edge of the dead zone and the end of the stick travel
+        assert_eq!(gamepads.poll(), [Action::LeftPaddleSteer(50)]);
+        assert_eq!(gamepads.poll(), [Action::LeftPaddleSteer(0)]);
+        assert_eq!(gamepads.poll(), [Action::LeftPaddleSteer(-100)]);
+    }
+
+    #[test]
+    fn dpad_goes_full_speed_over_the_stick() {
+        let mut gamepads = gamepads(
+            &GamepadConfig::default(),
+            vec![
+                vec![
+                    connected(0, "Pad"),
+                    axis(0, GamepadAxis::Stick, 0.6),
+                    axis(0, GamepadAxis::DPad, -1.0),
+                ],
+                vec![axis(0, GamepadAxis::DPad, 0.0)],
+            ],
+        );
+
+        assert_eq!(gamepads.poll(), [Action::LeftPaddleSteer(-100)]);
+        assert_eq!(gamepads.poll(), [Action::LeftPaddleSteer(50)]);
+    }
+
+    #[test]
+    fn unchanged_speeds_are_sent_once() {
+        let mut gamepads = gamepads(
+            &GamepadConfig::default(),
+            vec![
+                vec![connected(0, "Pad"), axis(0, GamepadAxis::Stick, 1.0)],
+                vec![axis(0, GamepadAxis::Stick, 1.0)],
+            ],
+        );
+
+        assert_eq!(gamepads.poll(), [Action::LeftPaddleSteer(100)]);
+        assert_eq!(gamepads.poll(), []);
+        gamepads.forget_sent();
+        assert_eq!(gamepads.poll(), [Action::LeftPaddleSteer(100)]);
+    }
+
+    #[test]
+    fn gamepads_plugged_in_take_the_free_paddles() {
+        let mut gamepads = gamepads(
+            &GamepadConfig::default(),
+            vec![
+                vec![connected(0, "First"), connected(1, "Second")],
+                vec![
+                    axis(0, GamepadAxis::DPad, 1.0),
+                    GamepadEvent::Disconnected { id: 0 },
+                ],
+                vec![connected(2, "Third"), axis(2, GamepadAxis::DPad, 1.0)],
+            ],
+        );
+
+        gamepads.poll();
+        assert_eq!(gamepads.side_of(0), Some(Side::Left));
+        assert_eq!(gamepads.side_of(1), Some(Side::Right));
+
+        // The left paddle must stop when its gamepad goes away mid-move
+        assert_eq!(gamepads.poll(), [Action::LeftPaddleStop]);
+        assert_eq!(gamepads.side_of(0), None);
+
+        assert_eq!(gamepads.poll(), [Action::LeftPaddleSteer(100)]);
+        assert_eq!(gamepads.side_of(2), Some(Side::Left));
+    }
+
+    #[test]
+    fn configuration_chooses_the_gamepad_of_a_paddle() {
+        let config = GamepadConfig {
+            right: Some(DeviceChoice::Name("x-box".to_string())),
+            ..GamepadConfig::default()
+        };
+        let mut gamepads = gamepads(
+            &config,
+            vec![vec![
+                connected(0, "Generic USB Joystick"),
+                connected(1, "Generic USB Joystick"),
+                connected(2, "Microsoft X-Box 360 pad"),
+            ]],
+        );
+
+        gamepads.poll();
+        assert_eq!(gamepads.side_of(0), Some(Side::Left));
+        assert_eq!(gamepads.side_of(1), None);
+        assert_eq!(gamepads.side_of(2), Some(Side::Right));
+
+        gamepads.swap_sides();
+        assert_eq!(gamepads.side_of(0), Some(Side::Right));
+        assert_eq!(gamepads.side_of(2), Some(Side::Left));
+    }
+}
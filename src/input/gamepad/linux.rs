@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use super::{GamepadAxis, GamepadBackend, GamepadEvent, GamepadId};
+
+/// Devices looked for, `/dev/input/js0` to `/dev/input/js15`
+const MAX_JOYSTICKS: GamepadId = 16;
+/// Time between two looks for newly plugged gamepads
+const SCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Kinds of `js_event`, see linux/joystick.h
+const JS_EVENT_AXIS: u8 = 0x02;
+/// Set on the events telling the state of the device when it is opened
+const JS_EVENT_INIT: u8 = 0x80;
+/// Size of a `js_event`: time stamp, value, kind and number of the axis or button
+const JS_EVENT_SIZE: usize = 8;
+
+/// Gamepads read from the joystick devices of Linux.
+///
+/// A thread looks for new devices every `SCAN_INTERVAL`, and each open device
+/// has a thread waiting for its events, which all end up in one channel.
+pub struct Joysticks {
+    events: Receiver<GamepadEvent>,
+}
+
+impl Joysticks {
+    /// Starts looking for gamepads, whose vertical stick and D-pad are the axes
+    /// `stick_axis` and `dpad_axis`.
+    pub fn start(stick_axis: u8, dpad_axis: u8) -> Joysticks {
+        let (sender, events) = mpsc::channel();
+        let axes = Axes {
+            stick: stick_axis,
+            dpad: dpad_axis,
+        };
+        thread::spawn(move || scan(axes, sender));
+        Joysticks { events }
+    }
+}
+
+impl GamepadBackend for Joysticks {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        self.events.try_iter().collect()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Axes {
+    stick: u8,
+    dpad: u8,
+}
+
+/// Opens the devices that appear, for as long as the game runs.
+fn scan(axes: Axes, sender: Sender<GamepadEvent>) {
+    let open = Arc::new(Mutex::new(HashSet::new()));
+    // Devices that can't be opened are reported once, not every scan
+    let mut refused = HashSet::new();
+    loop {
+        for id in 0..MAX_JOYSTICKS {
+            let path = format!("/dev/input/js{}", id);
+            if open.lock().unwrap().contains(&id) || !Path::new(&path).exists() {
+                continue;
+            }
+            match File::open(&path) {
+                Ok(file) => {
+                    refused.remove(&id);
+                    open.lock().unwrap().insert(id);
+                    let sender = sender.clone();
+                    let open = Arc::clone(&open);
+                    thread::spawn(move || {
+                        read_events(id, file, axes, &sender);
+                        open.lock().unwrap().remove(&id);
+                        let _ = sender.send(GamepadEvent::Disconnected { id });
+                    });
+                }
+                Err(error) => {
+                    if refused.insert(id) {
+                        log::warn!("Could not open the gamepad {}: {}", path, error);
+                    }
+                }
+            }
+        }
+        thread::sleep(SCAN_INTERVAL);
+    }
+}
+
+/// Sends the events of the device `id` until it is unplugged.
+fn read_events(id: GamepadId, mut file: File, axes: Axes, sender: &Sender<GamepadEvent>) {
+    let name = std::fs::read_to_string(format!("/sys/class/input/js{}/device/name", id))
+        .map(|name| name.trim().to_string())
+        .unwrap_or_else(|_| format!("Joystick {}", id));
+    if sender.send(GamepadEvent::Connected { id, name }).is_err() {
+        return;
+    }
+
+    let mut buffer = [0; JS_EVENT_SIZE];
+    while file.read_exact(&mut buffer).is_ok() {
+        let value = i16::from_ne_bytes([buffer[4], buffer[5]]);
+        let kind = buffer[6] & !JS_EVENT_INIT;
+        let number = buffer[7];
+        let axis = match number {
+            _ if kind != JS_EVENT_AXIS => continue,
+            number if number == axes.stick => GamepadAxis::Stick,
+            number if number == axes.dpad => GamepadAxis::DPad,
+            _ => continue,
+        };
+        let event = GamepadEvent::Axis {
+            id,
+            axis,
+            value: f32::from(value) / f32::from(i16::MAX),
+        };
+        if sender.send(event).is_err() {
+            return;
+        }
+    }
+}
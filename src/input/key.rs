@@ -0,0 +1,164 @@
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer};
+
+/// Declares `Key` with the name of each key in files and menus, and how
+/// winit's key codes translate to it. Variants are named like winit's.
+macro_rules! keys {
+    ($($key:ident => $name:expr,)*) => {
+        /// A key of the keyboard that can be bound to an `InputAction`.
+        ///
+        /// Keys are told apart by the symbol the keyboard layout puts on them, so
+        /// `Z` is the key labelled Z on AZERTY and QWERTY keyboards alike.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Key {
+            $($key,)*
+        }
+
+        impl Key {
+            pub const ALL: &'static [Key] = &[$(Key::$key,)*];
+
+            /// Name of the key in files and menus.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Key::$key => $name,)*
+                }
+            }
+
+            #[cfg(feature = "renderer")]
+            pub fn from_keycode(keycode: winit::event::VirtualKeyCode) -> Option<Key> {
+                match keycode {
+                    $(winit::event::VirtualKeyCode::$key => Some(Key::$key),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+keys! {
+    Key1 => "1",
+    Key2 => "2",
+    Key3 => "3",
+    Key4 => "4",
+    Key5 => "5",
+    Key6 => "6",
+    Key7 => "7",
+    Key8 => "8",
+    Key9 => "9",
+    Key0 => "0",
+    A => "A",
+    B => "B",
+    C => "C",
+    D => "D",
+    E => "E",
+    F => "F",
+    G => "G",
+    H => "H",
+    I => "I",
+    J => "J",
+    K => "K",
+    L => "L",
+    M => "M",
+    N => "N",
+    O => "O",
+    P => "P",
+    Q => "Q",
+    R => "R",
+    S => "S",
+    T => "T",
+    U => "U",
+    V => "V",
+    W => "W",
+    X => "X",
+    Y => "Y",
+    Z => "Z",
+    Escape => "Escape",
+    F1 => "F1",
+    F2 => "F2",
+    F3 => "F3",
+    F4 => "F4",
+    F5 => "F5",
+    F6 => "F6",
+    F7 => "F7",
+    F8 => "F8",
+    F9 => "F9",
+    F10 => "F10",
+    F11 => "F11",
+    F12 => "F12",
+    Insert => "Insert",
+    Home => "Home",
+    Delete => "Delete",
+    End => "End",
+    PageDown => "PageDown",
+    PageUp => "PageUp",
+    Left => "Left",
+    Up => "Up",
+    Right => "Right",
+    Down => "Down",
+    Back => "Backspace",
+    Return => "Enter",
+    Space => "Space",
+    Numpad0 => "Numpad0",
+    Numpad1 => "Numpad1",
+    Numpad2 => "Numpad2",
+    Numpad3 => "Numpad3",
+    Numpad4 => "Numpad4",
+    Numpad5 => "Numpad5",
+    Numpad6 => "Numpad6",
+    Numpad7 => "Numpad7",
+    Numpad8 => "Numpad8",
+    Numpad9 => "Numpad9",
+    NumpadEnter => "NumpadEnter",
+    Add => "NumpadAdd",
+    Subtract => "NumpadSubtract",
+    Multiply => "NumpadMultiply",
+    Divide => "NumpadDivide",
+    Decimal => "NumpadDecimal",
+    Apostrophe => "Apostrophe",
+    Backslash => "Backslash",
+    Comma => "Comma",
+    Equals => "Equals",
+    Grave => "Grave",
+    LBracket => "LBracket",
+    Minus => "Minus",
+    Period => "Period",
+    RBracket => "RBracket",
+    Semicolon => "Semicolon",
+    Slash => "Slash",
+    Tab => "Tab",
+    LAlt => "LAlt",
+    LControl => "LControl",
+    LShift => "LShift",
+    RAlt => "RAlt",
+    RControl => "RControl",
+    RShift => "RShift",
+}
+
+impl Key {
+    /// The key called `name`, whatever its case.
+    pub fn from_name(name: &str) -> Option<Key> {
+        Key::ALL
+            .iter()
+            .copied()
+            .find(|key| key.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Key, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Key::from_name(&name).ok_or_else(|| {
+            de::Error::custom(format!(
+                "unknown key `{}`, expected a letter, a digit or a name like `Space` or `Up`",
+                name
+            ))
+        })
+    }
+}
@@ -0,0 +1,101 @@
+use crate::scene::{Action, Scene};
+use crate::score::Side;
+
+/// A paddle following the height of the mouse cursor on the court.
+///
+/// The paddle goes no faster than its top speed, and slows down as it gets
+/// close to the cursor so that it stops on it rather than around it.
+pub struct MousePaddle {
+    side: Side,
+    /// Height of the court the cursor was last seen over, downwards being positive
+    target: Option<f32>,
+}
+
+impl MousePaddle {
+    pub fn new(side: Side) -> Self {
+        Self { side, target: None }
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// Sends the paddle towards the height `y` of the court.
+    pub fn aim(&mut self, y: f32) {
+        self.target = Some(y);
+    }
+
+    /// Returns the action bringing the paddle closer to the cursor over the next
+    /// `delta_time` seconds, unless the paddle already does that.
+    pub fn update(&self, scene: &Scene, delta_time: f32) -> Option<Action> {
+        // Nothing to follow until the cursor comes over the window
+        let target = self.target?;
+        let paddle = match self.side {
+            Side::Left => &scene.left_paddle,
+            Side::Right => &scene.right_paddle,
+        };
+
+        let top_speed = paddle.top_speed(scene.paddle_speed);
+        let max_distance = top_speed * delta_time;
+        if max_distance <= 0.0 {
+            return None;
+        }
+        let speed = (target - paddle.position.y) / max_distance;
+        let action = Action::steer(self.side, speed);
+
+        // The paddle may have been steered from elsewhere, like with the keyboard
+        let percent = match action {
+            Action::LeftPaddleSteer(percent) | Action::RightPaddleSteer(percent) => percent,
+            _ => unreachable!("Action::steer only returns steering actions"),
+        };
+        if paddle.target_velocity == top_speed * f32::from(percent) / 100.0 {
+            return None;
+        }
+        Some(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELTA_TIME: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn paddle_goes_towards_the_cursor_at_most_at_paddle_speed() {
+        let mut scene = Scene::new(0);
+        let mut mouse = MousePaddle::new(Side::Left);
+        assert_eq!(mouse.update(&scene, DELTA_TIME), None);
+
+        mouse.aim(-2.0);
+        let action = mouse.update(&scene, DELTA_TIME).unwrap();
+        assert_eq!(action, Action::LeftPaddleSteer(-100));
+        // Once the scene steers the paddle at that speed, the same order is not sent again
+        scene.handle_action(action);
+        assert_eq!(mouse.update(&scene, DELTA_TIME), None);
+
+        for _ in 0..120 {
+            if let Some(action) = mouse.update(&scene, DELTA_TIME) {
+                scene.handle_action(action);
+            }
+            scene.update(DELTA_TIME);
+        }
+        assert!((scene.left_paddle.position.y + 2.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn paddle_slows_down_close_to_the_cursor() {
+        let scene = Scene::new(0);
+        let mut mouse = MousePaddle::new(Side::Right);
+
+        // Half of what the paddle could travel in one step
+        mouse.aim(scene.paddle_speed * DELTA_TIME / 2.0);
+        assert_eq!(
+            mouse.update(&scene, DELTA_TIME),
+            Some(Action::RightPaddleSteer(50))
+        );
+        // Already there, and not moving
+        mouse.aim(0.0);
+        assert_eq!(mouse.update(&scene, DELTA_TIME), None);
+    }
+}
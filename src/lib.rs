@@ -0,0 +1,30 @@
+//! Pong simulation, usable without a window or a graphics card.
+//!
+//! The Vulkan renderer and the windowed game are behind the `renderer` feature.
+
+pub mod ai;
+#[cfg(feature = "renderer")]
+pub mod app;
+pub mod ball;
+pub mod camera;
+pub mod collision;
+pub mod config;
+pub mod deflection;
+pub mod game;
+#[cfg(feature = "renderer")]
+mod graphics_manager;
+pub mod headless;
+#[cfg(feature = "renderer")]
+pub mod hud;
+pub mod input;
+pub mod logging;
+pub mod menu;
+pub mod mesh;
+pub mod paddle;
+pub mod rally;
+pub mod replay;
+pub mod scene;
+pub mod score;
+pub mod settings;
+pub mod timestep;
+pub mod wall;
@@ -0,0 +1,162 @@
+use std::fmt;
+use std::str::FromStr;
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Which messages get logged: a level for everything, and levels for some targets.
+///
+/// Written like `warn` or `info,vulkan=debug,pong_rust::game=trace`. A target
+/// covers the targets under it, and the longest one that matches decides.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogFilter {
+    level: LevelFilter,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl Default for LogFilter {
+    /// Release builds only report problems, others tell what the game is doing
+    fn default() -> Self {
+        let level = if cfg!(debug_assertions) {
+            LevelFilter::Info
+        } else {
+            LevelFilter::Warn
+        };
+        Self::new(level)
+    }
+}
+
+impl LogFilter {
+    pub fn new(level: LevelFilter) -> Self {
+        Self {
+            level,
+            targets: vec![],
+        }
+    }
+
+    /// Most detailed level logged for `target`.
+    pub fn level_of(&self, target: &str) -> LevelFilter {
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| {
+                target == prefix
+                    || (target.starts_with(prefix.as_str())
+                        && target[prefix.len()..].starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.level, |(_, level)| *level)
+    }
+
+    /// Most detailed level logged for any target.
+    pub fn max_level(&self) -> LevelFilter {
+        self.targets
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.level, Ord::max)
+    }
+}
+
+impl FromStr for LogFilter {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let parse_level = |level: &str| {
+            level.trim().parse::<LevelFilter>().map_err(|_| {
+                format!(
+                    "unknown log level `{}`, expected off, error, warn, info, debug or trace",
+                    level.trim()
+                )
+            })
+        };
+        let mut filter = LogFilter::default();
+        for part in text.split(',').filter(|part| !part.trim().is_empty()) {
+            match part.split_once('=') {
+                Some((target, level)) => filter
+                    .targets
+                    .push((target.trim().to_string(), parse_level(level)?)),
+                None => filter.level = parse_level(part)?,
+            }
+        }
+        Ok(filter)
+    }
+}
+
+impl fmt::Display for LogFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.level.to_string().to_lowercase())?;
+        for (target, level) in &self.targets {
+            write!(f, ",{}={}", target, level.to_string().to_lowercase())?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the messages `filter` lets through to the standard error.
+struct Logger {
+    filter: LogFilter,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level_of(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "[{:<5} {}] {}",
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Sends the messages of the game through `filter` to the standard error.
+///
+/// Only the first call sets the logger up, the ones after it change nothing.
+pub fn init(filter: LogFilter) {
+    let max_level = filter.max_level();
+    let logger = Box::new(Logger { filter });
+    if log::set_logger(Box::leak(logger)).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_are_read() {
+        let filter: LogFilter = "warn, vulkan=debug,pong_rust::game=trace".parse().unwrap();
+
+        assert_eq!(filter.level_of("pong_rust::scene"), LevelFilter::Warn);
+        assert_eq!(filter.level_of("vulkan::validation"), LevelFilter::Debug);
+        assert_eq!(filter.level_of("pong_rust::game"), LevelFilter::Trace);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+        assert_eq!(
+            filter.to_string(),
+            "warn,vulkan=debug,pong_rust::game=trace"
+        );
+        assert_eq!("off".parse(), Ok(LogFilter::new(LevelFilter::Off)));
+    }
+
+    #[test]
+    fn longest_matching_target_decides() {
+        let filter: LogFilter = "error,pong_rust=info,pong_rust::game=off".parse().unwrap();
+
+        assert_eq!(filter.level_of("pong_rust::game"), LevelFilter::Off);
+        assert_eq!(filter.level_of("pong_rust::gameplay"), LevelFilter::Info);
+        assert_eq!(filter.level_of("pong_rust"), LevelFilter::Info);
+        assert_eq!(filter.level_of("pong"), LevelFilter::Error);
+    }
+
+    #[test]
+    fn unknown_levels_are_rejected() {
+        assert!("loud".parse::<LogFilter>().is_err());
+        assert!("info,vulkan=chatty".parse::<LogFilter>().is_err());
+    }
+}
@@ -1,151 +1,144 @@
-mod ball;
-mod camera;
-mod graphics_manager;
-mod paddle;
-mod scene;
-mod wall;
+mod cli;
 
-use crate::graphics_manager::constants::IS_PAINT_FPS_COUNTER;
-use crate::graphics_manager::GraphicsManager;
+#[cfg(feature = "renderer")]
+use pong_rust::app;
+use pong_rust::ai::{CpuPlayer, Difficulty};
+use pong_rust::config::Config;
+use pong_rust::game::{Game, GamePhase};
+use pong_rust::headless::{self, DEFAULT_HEADLESS_TICKS};
+use pong_rust::logging;
+use pong_rust::replay::{Recorder, Replay, ReplayPlayer};
+use pong_rust::score::Side;
 
-use scene::Scene;
-use winit::event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent};
-use winit::event_loop::{ControlFlow, EventLoop};
+use cli::Cli;
 
-#[derive(PartialEq)]
-enum GamePhase {
-    Start,
-    Playing,
-    End,
-}
-
-enum Action {
-    Quit
-}
-
-enum PongRustActions {
-    SceneAction(scene::Action),
-    SystemAction(Action),
-}
-
-struct PongRust {
-    graphics_manager: GraphicsManager,
-    scene: Scene,
-    game_phase: GamePhase,
-}
-
-impl PongRust {
-    pub fn main_loop(mut self, event_loop: EventLoop<()>) {
-        let mut tick_counter = graphics_manager::fps_limiter::FPSLimiter::new();
-
-        event_loop.run(move |event, _, control_flow| match event {
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::CloseRequested => {
-                    self.graphics_manager.device_wait_idle();
-                    *control_flow = ControlFlow::Exit
-                }
-                WindowEvent::KeyboardInput { input, .. } => {
-                    if let Some(action) = self.handle_keyboard_input(input) {
-                        match action {
-                            PongRustActions::SystemAction(Action::Quit) => {
-                                self.graphics_manager.device_wait_idle();
-                                *control_flow = ControlFlow::Exit
-                            },
-                            PongRustActions::SceneAction(action) => {
-                                self.scene.handle_action(action);
-                            }
-                        }
-                    }
-                }
-                _ => {}
-            },
-            Event::MainEventsCleared => {
-                self.graphics_manager.window_request_redraw();
-            }
-            Event::RedrawRequested(_window_id) => {
-                let delta_time = tick_counter.delta_time();
-                if self.scene.game_over() {
-                    self.scene.handle_action(scene::Action::GameOver);
-                    self.game_phase = GamePhase::End;
-                }
-                self.scene.update(delta_time);
-                let transforms = self.scene.get_model_transforms();
-                self.graphics_manager.draw_frame(transforms);
-
-                if IS_PAINT_FPS_COUNTER {
-                    print!("FPS: {}\r", tick_counter.fps());
-                }
-
-                tick_counter.tick_frame();
+fn main() {
+    let cli = Cli::parse(std::env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("{}, run with --help to see the options", error);
+        std::process::exit(1);
+    });
+    if cli.help {
+        print!("{}", cli::help());
+        return;
+    }
+    let mut config =
+        Config::load(cli.config.as_deref(), &cli.overrides).unwrap_or_else(|error| {
+            eprintln!("Invalid configuration: {}", error);
+            std::process::exit(1);
+        });
+    logging::init(config.logging.level.clone());
+    if cli.list_devices {
+        #[cfg(feature = "renderer")]
+        {
+            if let Err(error) = app::list_devices(&config) {
+                eprintln!("Could not list the graphics cards: {}", error);
+                std::process::exit(1);
             }
-            Event::LoopDestroyed => self.graphics_manager.device_wait_idle(),
-            _ => (),
-        })
+            return;
+        }
+        #[cfg(not(feature = "renderer"))]
+        {
+            eprintln!("This build has no renderer, so it draws with no device");
+            std::process::exit(1);
+        }
     }
+    // A replay brings the seed and the gameplay configuration the game was recorded with
+    let replay = cli.replay.as_ref().map(|path| {
+        let replay = Replay::load(path).unwrap_or_else(|error| {
+            eprintln!("Could not load the replay {}: {}", path.display(), error);
+            std::process::exit(1);
+        });
+        if let Err(error) = config.use_replay(&replay) {
+            eprintln!("Invalid replay {}: {}", path.display(), error);
+            std::process::exit(1);
+        }
+        replay
+    });
+    let seed = match &replay {
+        Some(replay) => replay.seed,
+        None => cli.seed.unwrap_or_else(rand::random),
+    };
+    let settings = config.settings();
+    let simulation_rate = config.gameplay.simulation_rate;
+    // Printed whatever the logging level, as the seed is what a game is played again from
+    eprintln!("Seed: {}", seed);
+    let recorder = match (&cli.record, &replay) {
+        (Some(_), Some(_)) => {
+            eprintln!("--record can't be used with --replay");
+            std::process::exit(1);
+        }
+        (Some(path), None) => {
+            let header = Replay {
+                seed,
+                simulation_rate,
+                settings,
+                court: config.court,
+                left_paddle: config.left_paddle,
+                right_paddle: config.right_paddle,
+                rally: config.rally.clone(),
+                events: vec![],
+            };
+            Some(Recorder::create(path, &header).unwrap_or_else(|error| {
+                eprintln!(
+                    "Could not create the recording {}: {}",
+                    path.display(),
+                    error
+                );
+                std::process::exit(1);
+            }))
+        }
+        _ => None,
+    };
 
-    pub fn handle_keyboard_input(&mut self, input: KeyboardInput) -> Option<PongRustActions> {
-        let KeyboardInput {
-            virtual_keycode,
-            state,
-            ..
-        } = input;
-        match (virtual_keycode, state) {
-            (Some(VirtualKeyCode::Escape), ElementState::Pressed) => {
-                Some(PongRustActions::SystemAction(Action::Quit))
-            },
-            (Some(VirtualKeyCode::Space), ElementState::Pressed) => {
-                match self.game_phase {
-                    GamePhase::Start => {
-                        self.game_phase = GamePhase::Playing;
-                        Some(PongRustActions::SceneAction(scene::Action::Kickoff))
-
-                    },
-                    GamePhase::Playing => None,
-                    GamePhase::End => {
-                        self.game_phase = GamePhase::Start;
-                        Some(PongRustActions::SceneAction(scene::Action::ResetGame))
-                    }
+    // A replay skips the menus, which it has no input for, and so does a game nobody sees
+    let phase = if replay.is_some() || cli.headless {
+        GamePhase::Start
+    } else {
+        GamePhase::Menu
+    };
+    let mut game = Game::new(seed, settings, phase);
+    game.scene.set_court(config.court);
+    game.scene.left_paddle.motion = config.left_paddle;
+    game.scene.right_paddle.motion = config.right_paddle;
+    game.scene.rally = config.rally.clone();
+    if let Some(replay) = &replay {
+        game.replay(ReplayPlayer::new(replay));
+    }
+    if let Some(recorder) = recorder {
+        game.record(recorder);
+    }
 
-                }
-            },
-            (Some(VirtualKeyCode::W), ElementState::Pressed) => {
-                Some(PongRustActions::SceneAction(scene::Action::LeftPaddleUp))
-            },
-            (Some(VirtualKeyCode::W), ElementState::Released) => {
-                Some(PongRustActions::SceneAction(scene::Action::LeftPaddleStop))
-            },
-            (Some(VirtualKeyCode::S), ElementState::Pressed) => {
-                Some(PongRustActions::SceneAction(scene::Action::LeftPaddleDown))
-            },
-            (Some(VirtualKeyCode::S), ElementState::Released) => {
-                Some(PongRustActions::SceneAction(scene::Action::LeftPaddleStop))
-            },
-            (Some(VirtualKeyCode::I), ElementState::Pressed) => {
-                Some(PongRustActions::SceneAction(scene::Action::RightPaddleUp))
-            },
-            (Some(VirtualKeyCode::I), ElementState::Released) => {
-                Some(PongRustActions::SceneAction(scene::Action::RightPaddleStop))
-            },
-            (Some(VirtualKeyCode::K), ElementState::Pressed) => {
-                Some(PongRustActions::SceneAction(scene::Action::RightPaddleDown))
-            },
-            (Some(VirtualKeyCode::K), ElementState::Released) => {
-                Some(PongRustActions::SceneAction(scene::Action::RightPaddleStop))
-            },
-            _ => None
+    if cli.headless {
+        if !game.is_replay() {
+            // Nobody is at the keyboard, so the computer plays both sides
+            let left = CpuPlayer::new(Side::Left, Difficulty::Easy, seed.wrapping_add(1));
+            game.add_cpu_player(left);
+            if !game.is_cpu_playing(Side::Right) {
+                let right = CpuPlayer::new(Side::Right, Difficulty::Normal, seed.wrapping_add(2));
+                game.add_cpu_player(right);
+            }
+        }
+        let summary = headless::run(
+            &mut game,
+            cli.ticks.unwrap_or(DEFAULT_HEADLESS_TICKS),
+            1.0 / simulation_rate,
+        );
+        game.stop_recording();
+        if game.is_replay() && !game.is_replay_finished() {
+            log::warn!("Stopped before the end of the replay");
         }
+        println!("{}", summary);
+        return;
     }
-}
-
-fn main() {
-    let event_loop = EventLoop::new();
-    let scene = Scene::new();
-    let graphics_manager = GraphicsManager::new(&event_loop, &scene);
-    let pong_rust = PongRust {
-        graphics_manager,
-        scene,
-        game_phase: GamePhase::Start,
-    };
 
-    pong_rust.main_loop(event_loop);
+    #[cfg(feature = "renderer")]
+    if let Err(error) = app::run(game, &config) {
+        eprintln!("Could not start the game: {}", error);
+        std::process::exit(1);
+    }
+    #[cfg(not(feature = "renderer"))]
+    {
+        eprintln!("This build has no renderer, run it with --headless");
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,415 @@
+use crate::input::{InputAction, Key, KeyBindings};
+use crate::settings::{Setting, Settings};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuScreen {
+    Main,
+    Settings,
+    Controls,
+    Pause,
+}
+
+/// An entry of a menu screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuItem {
+    Play,
+    OpenSettings,
+    Quit,
+    Setting(Setting),
+    OpenControls,
+    /// The keys of an action, changed by pressing another one
+    Binding(InputAction),
+    SwapGamepads,
+    Back,
+    Resume,
+    MainMenu,
+}
+
+impl MenuScreen {
+    pub fn title(self) -> &'static str {
+        match self {
+            MenuScreen::Main => "PONG",
+            MenuScreen::Settings => "Settings",
+            MenuScreen::Controls => "Controls",
+            MenuScreen::Pause => "Paused",
+        }
+    }
+
+    pub fn items(self) -> &'static [MenuItem] {
+        match self {
+            MenuScreen::Main => &[MenuItem::Play, MenuItem::OpenSettings, MenuItem::Quit],
+            MenuScreen::Settings => &[
+                MenuItem::Setting(Setting::BallSpeed),
+                MenuItem::Setting(Setting::PaddleSpeed),
+                MenuItem::Setting(Setting::TargetScore),
+                MenuItem::Setting(Setting::GameMode),
+                MenuItem::Setting(Setting::Opponent),
+                MenuItem::OpenControls,
+                MenuItem::Back,
+            ],
+            MenuScreen::Controls => &[
+                MenuItem::Binding(InputAction::LeftPaddleUp),
+                MenuItem::Binding(InputAction::LeftPaddleDown),
+                MenuItem::Binding(InputAction::RightPaddleUp),
+                MenuItem::Binding(InputAction::RightPaddleDown),
+                MenuItem::Binding(InputAction::Serve),
+                MenuItem::Binding(InputAction::Pause),
+                MenuItem::Binding(InputAction::Back),
+                MenuItem::SwapGamepads,
+                MenuItem::Back,
+            ],
+            MenuScreen::Pause => &[MenuItem::Resume, MenuItem::MainMenu, MenuItem::Quit],
+        }
+    }
+
+    /// Screen that going back leads to, `None` for the ones that don't go back to another.
+    fn parent(self) -> Option<MenuScreen> {
+        match self {
+            MenuScreen::Settings => Some(MenuScreen::Main),
+            MenuScreen::Controls => Some(MenuScreen::Settings),
+            MenuScreen::Main | MenuScreen::Pause => None,
+        }
+    }
+}
+
+/// Keys that mean something in a menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+}
+
+impl MenuInput {
+    /// Menu input of `key`: the keys of the left paddle move through the items,
+    /// the ones of the right paddle change the selected value, and the serve and
+    /// back keys confirm and go back. Unbound arrows, Enter, Escape and Backspace
+    /// do the same whatever the bindings.
+    pub fn from_key(key: Key, bindings: &KeyBindings) -> Option<MenuInput> {
+        match bindings.action_of(key) {
+            Some(InputAction::LeftPaddleUp) => Some(MenuInput::Up),
+            Some(InputAction::LeftPaddleDown) => Some(MenuInput::Down),
+            Some(InputAction::RightPaddleUp) => Some(MenuInput::Right),
+            Some(InputAction::RightPaddleDown) => Some(MenuInput::Left),
+            Some(InputAction::Serve) => Some(MenuInput::Confirm),
+            Some(InputAction::Back) => Some(MenuInput::Back),
+            Some(InputAction::Pause) | None => match key {
+                Key::Up => Some(MenuInput::Up),
+                Key::Down => Some(MenuInput::Down),
+                Key::Left => Some(MenuInput::Left),
+                Key::Right => Some(MenuInput::Right),
+                Key::Return => Some(MenuInput::Confirm),
+                Key::Escape | Key::Back => Some(MenuInput::Back),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// What the game has to do after a menu input, besides redrawing the menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuCommand {
+    Play,
+    Resume,
+    MainMenu,
+    Quit,
+    /// A value in the settings changed and has to reach the scene
+    SettingsChanged,
+    /// The gamepads steering the left and right paddles trade places
+    SwapGamepads,
+}
+
+/// Which screen is shown and which of its items is selected.
+pub struct Menu {
+    screen: MenuScreen,
+    selected: usize,
+    /// Action waiting for the next key pressed to be bound to it
+    rebinding: Option<InputAction>,
+}
+
+impl Default for Menu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Menu {
+    pub fn new() -> Self {
+        Self {
+            screen: MenuScreen::Main,
+            selected: 0,
+            rebinding: None,
+        }
+    }
+
+    pub fn screen(&self) -> MenuScreen {
+        self.screen
+    }
+
+    /// Index of the selected item among the items of the current screen.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected_item(&self) -> MenuItem {
+        self.screen.items()[self.selected]
+    }
+
+    /// Shows `screen` with its first item selected.
+    pub fn open(&mut self, screen: MenuScreen) {
+        self.screen = screen;
+        self.selected = 0;
+        self.rebinding = None;
+    }
+
+    /// Action whose new key is awaited, every key pressed goes to `bind` until then.
+    pub fn rebinding(&self) -> Option<InputAction> {
+        self.rebinding
+    }
+
+    /// Binds `key` to the action waiting for one. An action `key` was already
+    /// bound to takes the keys of this one instead.
+    pub fn bind(&mut self, key: Key, bindings: &mut KeyBindings) {
+        if let Some(action) = self.rebinding.take() {
+            bindings.rebind_swapping(action, key);
+        }
+    }
+
+    /// Stops waiting for a key, the action keeps the ones it had.
+    pub fn cancel_rebinding(&mut self) {
+        self.rebinding = None;
+    }
+
+    /// Text of each item of the current screen, with the current values of the settings and keys.
+    pub fn labels(&self, settings: &Settings, bindings: &KeyBindings) -> Vec<String> {
+        self.screen
+            .items()
+            .iter()
+            .map(|item| match item {
+                MenuItem::Play => "Play".to_string(),
+                MenuItem::OpenSettings => "Settings".to_string(),
+                MenuItem::Quit => "Quit".to_string(),
+                MenuItem::Setting(setting) => {
+                    format!(
+                        "{}: < {} >",
+                        setting_name(*setting),
+                        settings.display(*setting)
+                    )
+                }
+                MenuItem::OpenControls => "Controls".to_string(),
+                MenuItem::Binding(action) if self.rebinding == Some(*action) => {
+                    format!("{}: press a key", action.name())
+                }
+                MenuItem::Binding(action) => {
+                    let keys: Vec<&str> = bindings
+                        .keys(*action)
+                        .iter()
+                        .map(|key| key.name())
+                        .collect();
+                    let keys = if keys.is_empty() {
+                        "none".to_string()
+                    } else {
+                        keys.join(", ")
+                    };
+                    if bindings.has_conflict(*action) {
+                        format!("{}: {} (conflict)", action.name(), keys)
+                    } else {
+                        format!("{}: {}", action.name(), keys)
+                    }
+                }
+                MenuItem::SwapGamepads => "Swap gamepads".to_string(),
+                MenuItem::Back => "Back".to_string(),
+                MenuItem::Resume => "Resume".to_string(),
+                MenuItem::MainMenu => "Main menu".to_string(),
+            })
+            .collect()
+    }
+
+    pub fn handle_input(
+        &mut self,
+        input: MenuInput,
+        settings: &mut Settings,
+    ) -> Option<MenuCommand> {
+        let item_count = self.screen.items().len();
+        match input {
+            MenuInput::Up => {
+                self.selected = (self.selected + item_count - 1) % item_count;
+                None
+            }
+            MenuInput::Down => {
+                self.selected = (self.selected + 1) % item_count;
+                None
+            }
+            MenuInput::Left | MenuInput::Right => match self.selected_item() {
+                MenuItem::Setting(setting) => {
+                    let steps = if input == MenuInput::Left { -1 } else { 1 };
+                    settings.adjust(setting, steps);
+                    Some(MenuCommand::SettingsChanged)
+                }
+                _ => None,
+            },
+            MenuInput::Confirm => match self.selected_item() {
+                MenuItem::Play => Some(MenuCommand::Play),
+                MenuItem::OpenSettings => {
+                    self.open(MenuScreen::Settings);
+                    None
+                }
+                MenuItem::Quit => Some(MenuCommand::Quit),
+                MenuItem::Setting(setting) => {
+                    settings.adjust(setting, 1);
+                    Some(MenuCommand::SettingsChanged)
+                }
+                MenuItem::OpenControls => {
+                    self.open(MenuScreen::Controls);
+                    None
+                }
+                MenuItem::Binding(action) => {
+                    self.rebinding = Some(action);
+                    None
+                }
+                MenuItem::SwapGamepads => Some(MenuCommand::SwapGamepads),
+                MenuItem::Back => {
+                    self.open(self.screen.parent().unwrap_or(MenuScreen::Main));
+                    None
+                }
+                MenuItem::Resume => Some(MenuCommand::Resume),
+                MenuItem::MainMenu => Some(MenuCommand::MainMenu),
+            },
+            MenuInput::Back => match (self.screen, self.screen.parent()) {
+                (_, Some(parent)) => {
+                    self.open(parent);
+                    None
+                }
+                (MenuScreen::Pause, None) => Some(MenuCommand::Resume),
+                (_, None) => Some(MenuCommand::Quit),
+            },
+        }
+    }
+}
+
+fn setting_name(setting: Setting) -> &'static str {
+    match setting {
+        Setting::BallSpeed => "Ball speed",
+        Setting::PaddleSpeed => "Paddle speed",
+        Setting::TargetScore => "Score to win",
+        Setting::GameMode => "Game mode",
+        Setting::Opponent => "Opponent",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_wraps_around() {
+        let mut menu = Menu::new();
+        let mut settings = Settings::default();
+
+        menu.handle_input(MenuInput::Up, &mut settings);
+        assert_eq!(menu.selected_item(), MenuItem::Quit);
+
+        menu.handle_input(MenuInput::Down, &mut settings);
+        assert_eq!(menu.selected_item(), MenuItem::Play);
+    }
+
+    #[test]
+    fn settings_screen_is_reached_and_left() {
+        let mut menu = Menu::new();
+        let mut settings = Settings::default();
+
+        menu.handle_input(MenuInput::Down, &mut settings);
+        assert_eq!(menu.handle_input(MenuInput::Confirm, &mut settings), None);
+        assert_eq!(menu.screen(), MenuScreen::Settings);
+
+        assert_eq!(menu.handle_input(MenuInput::Back, &mut settings), None);
+        assert_eq!(menu.screen(), MenuScreen::Main);
+    }
+
+    #[test]
+    fn settings_are_changed_with_left_and_right() {
+        let mut menu = Menu::new();
+        let mut settings = Settings::default();
+        menu.open(MenuScreen::Settings);
+        // Score to win is the third setting
+        menu.handle_input(MenuInput::Down, &mut settings);
+        menu.handle_input(MenuInput::Down, &mut settings);
+
+        let command = menu.handle_input(MenuInput::Left, &mut settings);
+        assert_eq!(command, Some(MenuCommand::SettingsChanged));
+        assert_eq!(settings.target_score, Settings::default().target_score - 1);
+        assert!(menu.labels(&settings, &KeyBindings::default())[2].contains("10"));
+    }
+
+    #[test]
+    fn controls_are_rebound_with_the_next_key() {
+        let mut menu = Menu::new();
+        let mut settings = Settings::default();
+        let mut bindings = KeyBindings::default();
+        menu.open(MenuScreen::Controls);
+        // Right paddle up is the third action
+        menu.handle_input(MenuInput::Down, &mut settings);
+        menu.handle_input(MenuInput::Down, &mut settings);
+
+        menu.handle_input(MenuInput::Confirm, &mut settings);
+        assert_eq!(menu.rebinding(), Some(InputAction::RightPaddleUp));
+        assert_eq!(
+            menu.labels(&settings, &bindings)[2],
+            "Right paddle up: press a key"
+        );
+
+        menu.bind(Key::W, &mut bindings);
+        assert_eq!(menu.rebinding(), None);
+        assert_eq!(bindings.keys(InputAction::RightPaddleUp), [Key::W]);
+        let labels = menu.labels(&settings, &bindings);
+        assert_eq!(labels[0], "Left paddle up: I");
+        assert_eq!(labels[2], "Right paddle up: W");
+
+        // Pressing Escape while a key is awaited leaves the bindings alone
+        menu.handle_input(MenuInput::Confirm, &mut settings);
+        menu.cancel_rebinding();
+        assert_eq!(menu.rebinding(), None);
+        assert_eq!(bindings.keys(InputAction::RightPaddleUp), [Key::W]);
+
+        assert_eq!(menu.handle_input(MenuInput::Back, &mut settings), None);
+        assert_eq!(menu.screen(), MenuScreen::Settings);
+    }
+
+    #[test]
+    fn menus_follow_the_paddle_keys() {
+        let mut bindings = KeyBindings::default();
+        assert_eq!(MenuInput::from_key(Key::W, &bindings), Some(MenuInput::Up));
+        assert_eq!(
+            MenuInput::from_key(Key::K, &bindings),
+            Some(MenuInput::Left)
+        );
+        assert_eq!(
+            MenuInput::from_key(Key::Space, &bindings),
+            Some(MenuInput::Confirm)
+        );
+        assert_eq!(
+            MenuInput::from_key(Key::Down, &bindings),
+            Some(MenuInput::Down)
+        );
+        assert_eq!(MenuInput::from_key(Key::D, &bindings), None);
+
+        // Rebound keys move through the menus, the arrows still do
+        bindings.rebind(InputAction::LeftPaddleUp, Key::Z);
+        assert_eq!(MenuInput::from_key(Key::Z, &bindings), Some(MenuInput::Up));
+        assert_eq!(MenuInput::from_key(Key::W, &bindings), None);
+        assert_eq!(MenuInput::from_key(Key::Up, &bindings), Some(MenuInput::Up));
+    }
+
+    #[test]
+    fn pause_menu_resumes_on_back() {
+        let mut menu = Menu::new();
+        let mut settings = Settings::default();
+        menu.open(MenuScreen::Pause);
+
+        let command = menu.handle_input(MenuInput::Back, &mut settings);
+        assert_eq!(command, Some(MenuCommand::Resume));
+    }
+}
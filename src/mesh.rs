@@ -0,0 +1,13 @@
+/// Geometry of an object of the scene, a quad made of two triangles.
+#[derive(Clone)]
+pub struct ModelMesh {
+    pub vertices: [Vertex; 4],
+    pub indices: [u32; 6],
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Copy)]
+pub struct Vertex {
+    pub pos: [f32; 2],
+    pub color: [f32; 3],
+}
@@ -1,15 +1,51 @@
 use cgmath::Vector3;
+use serde::Deserialize;
 
-use crate::graphics_manager::structures::{ModelMesh, Vertex};
+use crate::mesh::{ModelMesh, Vertex};
 
 const INDICES_DATA: [u32; 6] = [0, 1, 2, 2, 3, 0];
 
 pub struct Paddle {
     pub model_mesh: ModelMesh,
     pub position: Vector3<f32>,
+    /// Speed the paddle moves at, downwards when positive
     pub velocity: f32,
+    /// Velocity its player asks for, which `velocity` reaches as fast as `motion` allows
+    pub target_velocity: f32,
     pub height: f32,
     pub width: f32,
+    pub motion: PaddleMotion,
+}
+
+/// How a paddle picks up speed, loses it, and meets the walls.
+///
+/// The default paddle has no inertia: it goes at the speed asked for at once,
+/// stops dead against the walls and leaves the ball to the deflection alone.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PaddleMotion {
+    /// Speed gained per second while a player pushes the paddle, `0` to reach it at once
+    pub acceleration: f32,
+    /// Speed lost per second once they let go or turn around, `0` to stop at once
+    pub deceleration: f32,
+    /// Speed of the paddle when pushed all the way, the paddle speed of the settings when not given
+    pub max_speed: Option<f32>,
+    /// Part of its speed the paddle keeps when it bounces off a wall, `0` to stop against it
+    pub bounce: f32,
+    /// Part of the vertical velocity of the paddle given to the ball it sends back
+    pub english: f32,
+}
+
+impl Default for PaddleMotion {
+    fn default() -> Self {
+        Self {
+            acceleration: 0.0,
+            deceleration: 0.0,
+            max_speed: None,
+            bounce: 0.0,
+            english: 0.0,
+        }
+    }
 }
 
 impl Paddle {
@@ -21,11 +57,46 @@ impl Paddle {
             },
             position,
             velocity: 0.0,
+            target_velocity: 0.0,
             height,
             width,
+            motion: PaddleMotion::default(),
         }
     }
 
+    /// Speed of the paddle when pushed all the way, given the `paddle_speed` of the settings.
+    pub fn top_speed(&self, paddle_speed: f32) -> f32 {
+        self.motion.max_speed.unwrap_or(paddle_speed)
+    }
+
+    /// Brings `velocity` closer to `target_velocity`, as much as the motion allows in `delta_time`.
+    pub fn accelerate(&mut self, delta_time: f32) {
+        let difference = self.target_velocity - self.velocity;
+        // Going faster the same way is accelerating, anything else slows the paddle down first
+        let rate = if self.velocity * difference >= 0.0 {
+            self.motion.acceleration
+        } else {
+            self.motion.deceleration
+        };
+        let change = rate * delta_time;
+        if rate <= 0.0 || difference.abs() <= change {
+            self.velocity = self.target_velocity;
+        } else {
+            self.velocity += change * difference.signum();
+        }
+    }
+
+    /// The paddle ran into a wall: it bounces back off it, or stops there.
+    pub fn hit_wall(&mut self) {
+        self.velocity *= -self.motion.bounce;
+    }
+
+    /// Stops the paddle at once, whatever its motion.
+    pub fn stop(&mut self) {
+        self.velocity = 0.0;
+        self.target_velocity = 0.0;
+    }
+
     fn vertices(height: f32, width: f32, color: [f32; 3]) -> [Vertex; 4] {
         let half_height = height / 2.0;
         let half_width = width / 2.0;
@@ -50,3 +121,64 @@ impl Paddle {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paddle(motion: PaddleMotion) -> Paddle {
+        let mut paddle = Paddle::new(Vector3::new(0.0, 0.0, 0.0), 2.0, 0.2, [1.0, 1.0, 1.0]);
+        paddle.motion = motion;
+        paddle
+    }
+
+    #[test]
+    fn paddle_without_inertia_goes_at_the_speed_asked_for() {
+        let mut paddle = paddle(PaddleMotion::default());
+        paddle.target_velocity = -2.0;
+        paddle.accelerate(0.01);
+
+        assert_eq!(paddle.velocity, -2.0);
+        assert_eq!(paddle.top_speed(2.0), 2.0);
+    }
+
+    #[test]
+    fn paddle_speeds_up_and_slows_down_at_its_own_rates() {
+        let mut paddle = paddle(PaddleMotion {
+            acceleration: 10.0,
+            deceleration: 20.0,
+            max_speed: Some(3.0),
+            ..PaddleMotion::default()
+        });
+        paddle.target_velocity = paddle.top_speed(2.0);
+
+        paddle.accelerate(0.1);
+        assert_eq!(paddle.velocity, 1.0);
+        paddle.accelerate(1.0);
+        assert_eq!(paddle.velocity, 3.0);
+
+        // Turning around brakes first, then speeds up the other way
+        paddle.target_velocity = -3.0;
+        paddle.accelerate(0.1);
+        assert_eq!(paddle.velocity, 1.0);
+        paddle.accelerate(0.05);
+        assert_eq!(paddle.velocity, 0.0);
+        paddle.accelerate(0.1);
+        assert_eq!(paddle.velocity, -1.0);
+    }
+
+    #[test]
+    fn paddle_bounces_off_walls_with_part_of_its_speed() {
+        let mut paddle = paddle(PaddleMotion {
+            bounce: 0.5,
+            ..PaddleMotion::default()
+        });
+        paddle.velocity = 2.0;
+        paddle.hit_wall();
+        assert_eq!(paddle.velocity, -1.0);
+
+        paddle.motion.bounce = 0.0;
+        paddle.hit_wall();
+        assert_eq!(paddle.velocity, 0.0);
+    }
+}
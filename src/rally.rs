@@ -0,0 +1,160 @@
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+
+/// How the speed of the ball changes at each paddle hit of a rally.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Progression {
+    /// The ball keeps the speed it comes at
+    Constant,
+    /// Each hit adds `RallySpeed::increment`
+    Linear,
+    /// Each hit multiplies the speed by `RallySpeed::factor`
+    Multiplicative,
+    /// Each hit gives the ball the next speed of `RallySpeed::steps`
+    Steps,
+}
+
+impl Progression {
+    pub const ALL: [Progression; 4] = [
+        Progression::Constant,
+        Progression::Linear,
+        Progression::Multiplicative,
+        Progression::Steps,
+    ];
+
+    /// Name of the progression in files.
+    pub fn id(self) -> &'static str {
+        match self {
+            Progression::Constant => "constant",
+            Progression::Linear => "linear",
+            Progression::Multiplicative => "multiplicative",
+            Progression::Steps => "steps",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Progression> {
+        Progression::ALL
+            .iter()
+            .copied()
+            .find(|progression| progression.id() == id)
+    }
+}
+
+/// How the ball speeds up along a rally, from the serve to the point.
+///
+/// Every serve starts again from the ball speed of the settings.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RallySpeed {
+    #[serde(deserialize_with = "progression")]
+    pub progression: Progression,
+    /// Speed added by each hit of a linear progression
+    pub increment: f32,
+    /// Factor applied by each hit of a multiplicative progression
+    pub factor: f32,
+    /// Speed after the first hit, the second and so on, the last one lasting
+    /// for the rest of the rally
+    pub steps: Vec<f32>,
+    /// Fastest a ball may leave a paddle
+    pub max_speed: f32,
+}
+
+impl Default for RallySpeed {
+    fn default() -> Self {
+        Self {
+            progression: Progression::Constant,
+            increment: 0.25,
+            factor: 1.05,
+            steps: vec![5.0, 6.0, 7.0],
+            max_speed: 12.0,
+        }
+    }
+}
+
+impl RallySpeed {
+    /// Speed of a ball that came at `speed` when leaving the paddle for the
+    /// `hits`-th time in the rally.
+    pub fn after_hit(&self, speed: f32, hits: u32) -> f32 {
+        let speed = match self.progression {
+            Progression::Constant => speed,
+            Progression::Linear => speed + self.increment,
+            Progression::Multiplicative => speed * self.factor,
+            Progression::Steps => self
+                .steps
+                .get(hits.max(1) as usize - 1)
+                .or_else(|| self.steps.last())
+                .copied()
+                .unwrap_or(speed),
+        };
+        speed.min(self.max_speed)
+    }
+}
+
+fn progression<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Progression, D::Error> {
+    let id = String::deserialize(deserializer)?;
+    Progression::from_id(&id).ok_or_else(|| {
+        de::Error::custom(format!(
+            "unknown progression `{}`, expected `constant`, `linear`, `multiplicative` or `steps`",
+            id
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speeds(rally: RallySpeed, hits: u32) -> Vec<f32> {
+        let rally = RallySpeed {
+            max_speed: 6.0,
+            ..rally
+        };
+        let mut speed = 4.0;
+        (1..=hits)
+            .map(|hit| {
+                speed = rally.after_hit(speed, hit);
+                speed
+            })
+            .collect()
+    }
+
+    #[test]
+    fn constant_rallies_keep_the_speed() {
+        assert_eq!(speeds(RallySpeed::default(), 3), [4.0, 4.0, 4.0]);
+        // Still held to the maximum, like a ball sped up by a moving paddle
+        assert_eq!(RallySpeed::default().after_hit(20.0, 1), 12.0);
+    }
+
+    #[test]
+    fn speed_grows_up_to_the_maximum() {
+        let linear = RallySpeed {
+            progression: Progression::Linear,
+            increment: 0.75,
+            ..RallySpeed::default()
+        };
+        let multiplicative = RallySpeed {
+            progression: Progression::Multiplicative,
+            factor: 1.25,
+            ..RallySpeed::default()
+        };
+
+        assert_eq!(speeds(linear, 4), [4.75, 5.5, 6.0, 6.0]);
+        assert_eq!(speeds(multiplicative, 3), [5.0, 6.0, 6.0]);
+    }
+
+    #[test]
+    fn steps_give_the_speed_of_each_hit() {
+        let rally = RallySpeed {
+            progression: Progression::Steps,
+            steps: vec![4.5, 5.0, 9.0],
+            ..RallySpeed::default()
+        };
+
+        assert_eq!(speeds(rally.clone(), 5), [4.5, 5.0, 6.0, 6.0, 6.0]);
+        let no_steps = RallySpeed {
+            steps: vec![],
+            ..rally
+        };
+        assert_eq!(speeds(no_steps, 2), [4.0, 4.0]);
+    }
+}
@@ -0,0 +1,587 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::config::check_settings;
+use crate::paddle::PaddleMotion;
+use crate::rally::{Progression, RallySpeed};
+use crate::scene::{Action, Court};
+use crate::score::Score;
+use crate::settings::{GameMode, Opponent, Settings};
+
+/// First word of every replay file
+const MAGIC: &str = "pong-rust-replay";
+/// Bumped whenever a change to the file format or to the simulation would make
+/// older replays play differently
+pub const REPLAY_VERSION: u32 = 6;
+
+/// Something that changed the course of a game, to be done again on replay.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReplayEvent {
+    Action(Action),
+    Settings(Settings),
+    /// Recording stopped here, with this score. Lets a replay check it ended the same way.
+    End(Score),
+}
+
+/// A recorded game: how the scene started, and the events stamped with the
+/// simulation step they happened before.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub simulation_rate: f32,
+    pub settings: Settings,
+    pub court: Court,
+    pub left_paddle: PaddleMotion,
+    pub right_paddle: PaddleMotion,
+    pub rally: RallySpeed,
+    pub events: Vec<(u64, ReplayEvent)>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    NotAReplay,
+    UnsupportedVersion(u32),
+    Malformed { line: usize, reason: String },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "{}", error),
+            ReplayError::NotAReplay => write!(f, "not a pong-rust replay file"),
+            ReplayError::UnsupportedVersion(version) => write!(
+                f,
+                "replay format version {} is not supported, this build plays version {}",
+                version, REPLAY_VERSION
+            ),
+            ReplayError::Malformed { line, reason } => write!(f, "line {}: {}", line, reason),
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Replay, ReplayError> {
+        Replay::parse(&std::fs::read_to_string(path)?)
+    }
+
+    fn parse(text: &str) -> Result<Replay, ReplayError> {
+        let lines: Vec<&str> = text.lines().collect();
+
+        let mut words = lines.first().copied().unwrap_or("").split_whitespace();
+        if words.next() != Some(MAGIC) {
+            return Err(ReplayError::NotAReplay);
+        }
+        let version = words
+            .next()
+            .and_then(|version| version.parse().ok())
+            .ok_or(ReplayError::NotAReplay)?;
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        // The header holds one named value per line, in a fixed order
+        let header = |index: usize, name: &str| match lines.get(index)?.split_once(' ') {
+            Some((key, value)) if key == name => Some(value),
+            _ => None,
+        };
+        let seed = header(1, "seed")
+            .and_then(|seed| seed.parse().ok())
+            .ok_or_else(|| malformed(2, "expected the seed"))?;
+        let simulation_rate = header(2, "rate")
+            .and_then(|rate| rate.parse().ok())
+            .filter(|rate: &f32| *rate > 0.0)
+            .ok_or_else(|| malformed(3, "expected the simulation rate"))?;
+        let settings = header(3, "settings")
+            .and_then(decode_settings)
+            .ok_or_else(|| malformed(4, "expected the settings"))?;
+        let court = header(4, "court")
+            .and_then(decode_court)
+            .ok_or_else(|| malformed(5, "expected the court"))?;
+        let left_paddle = header(5, "left-paddle")
+            .and_then(decode_motion)
+            .ok_or_else(|| malformed(6, "expected the motion of the left paddle"))?;
+        let right_paddle = header(6, "right-paddle")
+            .and_then(decode_motion)
+            .ok_or_else(|| malformed(7, "expected the motion of the right paddle"))?;
+        let rally = header(7, "rally")
+            .and_then(decode_rally)
+            .ok_or_else(|| malformed(8, "expected the rally speed"))?;
+
+        let mut events = vec![];
+        for (index, text) in lines.iter().enumerate().skip(8) {
+            if text.trim().is_empty() {
+                continue;
+            }
+            let line = index + 1;
+            let (tick, event) = text
+                .split_once(' ')
+                .ok_or_else(|| malformed(line, "expected a step and an event"))?;
+            let tick = tick.parse().map_err(|_| malformed(line, "invalid step"))?;
+            let event = decode_event(event).ok_or_else(|| malformed(line, "unknown event"))?;
+            // Changed settings must be as valid as the ones the game started with
+            if let ReplayEvent::Settings(settings) = &event {
+                check_settings(settings).map_err(|error| malformed(line, error.to_string()))?;
+            }
+            events.push((tick, event));
+        }
+
+        Ok(Replay {
+            seed,
+            simulation_rate,
+            settings,
+            court,
+            left_paddle,
+            right_paddle,
+            rally,
+            events,
+        })
+    }
+}
+
+fn malformed(line: usize, reason: impl Into<String>) -> ReplayError {
+    ReplayError::Malformed {
+        line,
+        reason: reason.into(),
+    }
+}
+
+/// Writes a replay file as the game goes, so it survives the game closing abruptly.
+pub struct Recorder {
+    file: File,
+}
+
+impl Recorder {
+    /// Starts the file of `replay` at `path`, with the events it already has.
+    pub fn create(path: &Path, replay: &Replay) -> io::Result<Recorder> {
+        let mut file = File::create(path)?;
+        writeln!(file, "{} {}", MAGIC, REPLAY_VERSION)?;
+        writeln!(file, "seed {}", replay.seed)?;
+        writeln!(file, "rate {}", replay.simulation_rate)?;
+        writeln!(file, "settings {}", encode_settings(&replay.settings))?;
+        writeln!(file, "court {}", encode_court(&replay.court))?;
+        writeln!(file, "left-paddle {}", encode_motion(&replay.left_paddle))?;
+        writeln!(file, "right-paddle {}", encode_motion(&replay.right_paddle))?;
+        writeln!(file, "rally {}", encode_rally(&replay.rally))?;
+
+        let mut recorder = Recorder { file };
+        for (tick, event) in &replay.events {
+            recorder.record(*tick, event)?;
+        }
+        Ok(recorder)
+    }
+
+    pub fn record(&mut self, tick: u64, event: &ReplayEvent) -> io::Result<()> {
+        writeln!(self.file, "{} {}", tick, encode_event(event))
+    }
+}
+
+/// Hands the events of a replay back as the simulation reaches them.
+pub struct ReplayPlayer {
+    events: VecDeque<(u64, ReplayEvent)>,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: &Replay) -> Self {
+        Self {
+            events: replay.events.iter().copied().collect(),
+        }
+    }
+
+    /// Next event due before simulation step `tick`, if any.
+    pub fn next_event(&mut self, tick: u64) -> Option<ReplayEvent> {
+        match self.events.front() {
+            Some((event_tick, _)) if *event_tick <= tick => {
+                self.events.pop_front().map(|(_, event)| event)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+fn encode_event(event: &ReplayEvent) -> String {
+    match event {
+        ReplayEvent::Action(action) => match action {
+            Action::LeftPaddleSteer(percent) | Action::RightPaddleSteer(percent) => {
+                format!("{} {}", action_name(*action), percent)
+            }
+            _ => action_name(*action).to_string(),
+        },
+        ReplayEvent::Settings(settings) => format!("settings {}", encode_settings(settings)),
+        ReplayEvent::End(score) => format!("end {} {}", score.left, score.right),
+    }
+}
+
+fn decode_event(text: &str) -> Option<ReplayEvent> {
+    let (name, arguments) = text.split_once(' ').unwrap_or((text, ""));
+    match name {
+        "settings" => decode_settings(arguments).map(ReplayEvent::Settings),
+        "end" => {
+            let (left, right) = arguments.split_once(' ')?;
+            Some(ReplayEvent::End(Score {
+                left: left.parse().ok()?,
+                right: right.parse().ok()?,
+            }))
+        }
+        "left-steer" => steer_percent(arguments)
+            .map(|percent| ReplayEvent::Action(Action::LeftPaddleSteer(percent))),
+        "right-steer" => steer_percent(arguments)
+            .map(|percent| ReplayEvent::Action(Action::RightPaddleSteer(percent))),
+        _ => ACTIONS
+            .iter()
+            .find(|action| action_name(**action) == name)
+            .map(|action| ReplayEvent::Action(*action)),
+    }
+}
+
+const ACTIONS: [Action; 9] = [
+    Action::LeftPaddleUp,
+    Action::LeftPaddleDown,
+    Action::LeftPaddleStop,
+    Action::RightPaddleUp,
+    Action::RightPaddleDown,
+    Action::RightPaddleStop,
+    Action::Kickoff,
+    Action::GameOver,
+    Action::ResetGame,
+];
+
+/// Names of the actions in replay files, which must not change along with the code.
+///
+/// Steering actions are followed by their percentage of the paddle speed.
+fn action_name(action: Action) -> &'static str {
+    match action {
+        Action::LeftPaddleUp => "left-up",
+        Action::LeftPaddleDown => "left-down",
+        Action::LeftPaddleStop => "left-stop",
+        Action::RightPaddleUp => "right-up",
+        Action::RightPaddleDown => "right-down",
+        Action::RightPaddleStop => "right-stop",
+        Action::Kickoff => "kickoff",
+        Action::GameOver => "game-over",
+        Action::ResetGame => "reset-game",
+        Action::LeftPaddleSteer(_) => "left-steer",
+        Action::RightPaddleSteer(_) => "right-steer",
+    }
+}
+
+fn steer_percent(text: &str) -> Option<i8> {
+    text.parse()
+        .ok()
+        .filter(|percent: &i8| (-100..=100).contains(percent))
+}
+
+fn encode_settings(settings: &Settings) -> String {
+    format!(
+        "ball_speed={} paddle_speed={} target_score={} game_mode={} opponent={}",
+        settings.ball_speed,
+        settings.paddle_speed,
+        settings.target_score,
+        settings.game_mode.id(),
+        settings.opponent.id()
+    )
+}
+
+fn decode_settings(text: &str) -> Option<Settings> {
+    let mut settings = Settings::default();
+    for field in text.split_whitespace() {
+        let (key, value) = field.split_once('=')?;
+        match key {
+            "ball_speed" => settings.ball_speed = value.parse().ok()?,
+            "paddle_speed" => settings.paddle_speed = value.parse().ok()?,
+            "target_score" => settings.target_score = value.parse().ok()?,
+            "game_mode" => settings.game_mode = GameMode::from_id(value)?,
+            "opponent" => settings.opponent = Opponent::from_id(value)?,
+            _ => return None,
+        }
+    }
+
+    Some(settings)
+}
+
+fn encode_court(court: &Court) -> String {
+    format!(
+        "wall_distance={} out_of_bounds={}",
+        court.wall_distance, court.out_of_bounds
+    )
+}
+
+fn decode_court(text: &str) -> Option<Court> {
+    let mut court = Court::default();
+    for field in text.split_whitespace() {
+        let (key, value) = field.split_once('=')?;
+        match key {
+            "wall_distance" => court.wall_distance = value.parse().ok()?,
+            "out_of_bounds" => court.out_of_bounds = value.parse().ok()?,
+            _ => return None,
+        }
+    }
+
+    Some(court)
+}
+
+/// Encodes a paddle `motion`, leaving out the top speed when it follows the settings.
+fn encode_motion(motion: &PaddleMotion) -> String {
+    let mut text = format!(
+        "acceleration={} deceleration={} bounce={} english={}",
+        motion.acceleration, motion.deceleration, motion.bounce, motion.english
+    );
+    if let Some(max_speed) = motion.max_speed {
+        text += &format!(" max_speed={}", max_speed);
+    }
+    text
+}
+
+fn decode_motion(text: &str) -> Option<PaddleMotion> {
+    let mut motion = PaddleMotion::default();
+    for field in text.split_whitespace() {
+        let (key, value) = field.split_once('=')?;
+        match key {
+            "acceleration" => motion.acceleration = value.parse().ok()?,
+            "deceleration" => motion.deceleration = value.parse().ok()?,
+            "max_speed" => motion.max_speed = Some(value.parse().ok()?),
+            "bounce" => motion.bounce = value.parse().ok()?,
+            "english" => motion.english = value.parse().ok()?,
+            _ => return None,
+        }
+    }
+
+    Some(motion)
+}
+
+fn encode_rally(rally: &RallySpeed) -> String {
+    let steps: Vec<String> = rally.steps.iter().map(f32::to_string).collect();
+    format!(
+        "progression={} increment={} factor={} steps={} max_speed={}",
+        rally.progression.id(),
+        rally.increment,
+        rally.factor,
+        steps.join(","),
+        rally.max_speed
+    )
+}
+
+fn decode_rally(text: &str) -> Option<RallySpeed> {
+    let mut rally = RallySpeed::default();
+    for field in text.split_whitespace() {
+        let (key, value) = field.split_once('=')?;
+        match key {
+            "progression" => rally.progression = Progression::from_id(value)?,
+            "increment" => rally.increment = value.parse().ok()?,
+            "factor" => rally.factor = value.parse().ok()?,
+            "steps" => {
+                rally.steps = value
+                    .split(',')
+                    .filter(|step| !step.is_empty())
+                    .map(|step| step.parse().ok())
+                    .collect::<Option<_>>()?
+            }
+            "max_speed" => rally.max_speed = value.parse().ok()?,
+            _ => return None,
+        }
+    }
+
+    Some(rally)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::Difficulty;
+    use crate::game::{Game, GamePhase};
+
+    fn sample_replay() -> Replay {
+        let settings = Settings {
+            ball_speed: 5.5,
+            opponent: Opponent::Cpu(Difficulty::Normal),
+            ..Settings::default()
+        };
+
+        Replay {
+            seed: 42,
+            simulation_rate: 120.0,
+            settings,
+            court: Court {
+                wall_distance: 2.5,
+                ..Court::default()
+            },
+            left_paddle: PaddleMotion::default(),
+            right_paddle: PaddleMotion {
+                acceleration: 12.0,
+                max_speed: Some(3.5),
+                english: 0.25,
+                ..PaddleMotion::default()
+            },
+            rally: RallySpeed {
+                progression: Progression::Steps,
+                steps: vec![4.5, 6.0],
+                ..RallySpeed::default()
+            },
+            events: vec![
+                (0, ReplayEvent::Action(Action::Kickoff)),
+                (12, ReplayEvent::Action(Action::LeftPaddleUp)),
+                (20, ReplayEvent::Action(Action::RightPaddleSteer(-35))),
+                (30, ReplayEvent::Settings(Settings::default())),
+                (40, ReplayEvent::End(Score { left: 1, right: 0 })),
+            ],
+        }
+    }
+
+    fn to_text(replay: &Replay) -> String {
+        let mut text = format!(
+            "{} {}\nseed {}\nrate {}\nsettings {}\ncourt {}\nleft-paddle {}\nright-paddle {}\nrally {}\n",
+            MAGIC,
+            REPLAY_VERSION,
+            replay.seed,
+            replay.simulation_rate,
+            encode_settings(&replay.settings),
+            encode_court(&replay.court),
+            encode_motion(&replay.left_paddle),
+            encode_motion(&replay.right_paddle),
+            encode_rally(&replay.rally)
+        );
+        for (tick, event) in &replay.events {
+            text += &format!("{} {}\n", tick, encode_event(event));
+        }
+        text
+    }
+
+    #[test]
+    fn replay_survives_a_round_trip() {
+        let replay = sample_replay();
+        assert_eq!(Replay::parse(&to_text(&replay)).unwrap(), replay);
+    }
+
+    #[test]
+    fn recorder_writes_a_readable_file() {
+        let path = std::env::temp_dir().join(format!("pong-rust-replay-{}", std::process::id()));
+        let replay = sample_replay();
+        let header = Replay {
+            events: vec![],
+            ..replay.clone()
+        };
+        let mut recorder = Recorder::create(&path, &header).unwrap();
+        for (tick, event) in &replay.events {
+            recorder.record(*tick, event).unwrap();
+        }
+
+        let loaded = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), replay);
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let text = to_text(&sample_replay()).replacen(
+            &format!("{} {}", MAGIC, REPLAY_VERSION),
+            &format!("{} {}", MAGIC, REPLAY_VERSION + 1),
+            1,
+        );
+        assert!(matches!(
+            Replay::parse(&text),
+            Err(ReplayError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(
+            Replay::parse("not a replay"),
+            Err(ReplayError::NotAReplay)
+        ));
+    }
+
+    #[test]
+    fn settings_events_out_of_range_are_rejected() {
+        let mut replay = sample_replay();
+        replay.events.push((
+            50,
+            ReplayEvent::Settings(Settings {
+                ball_speed: 40.0,
+                ..Settings::default()
+            }),
+        ));
+        let line = 8 + replay.events.len();
+        assert!(matches!(
+            Replay::parse(&to_text(&replay)),
+            Err(ReplayError::Malformed { line: at, .. }) if at == line
+        ));
+    }
+
+    #[test]
+    fn events_come_back_at_their_step() {
+        let mut player = ReplayPlayer::new(&sample_replay());
+        assert_eq!(
+            player.next_event(0),
+            Some(ReplayEvent::Action(Action::Kickoff))
+        );
+        assert_eq!(player.next_event(0), None);
+        assert_eq!(player.next_event(11), None);
+        assert_eq!(
+            player.next_event(12),
+            Some(ReplayEvent::Action(Action::LeftPaddleUp))
+        );
+    }
+
+    #[test]
+    fn replayed_actions_reproduce_the_game() {
+        let path = std::env::temp_dir().join(format!("pong-rust-game-{}", std::process::id()));
+        let replay = sample_replay();
+        let header = Replay {
+            events: vec![],
+            ..replay.clone()
+        };
+        let actions = [
+            (50, Action::LeftPaddleDown),
+            (90, Action::LeftPaddleStop),
+            (400, Action::RightPaddleUp),
+            (700, Action::RightPaddleSteer(60)),
+        ];
+
+        // Player actions and a change of settings come between fixed steps, like in the game loop
+        let mut recorded = Game::new(replay.seed, replay.settings, GamePhase::Start);
+        recorded.record(Recorder::create(&path, &header).unwrap());
+        recorded.kickoff();
+        for tick in 0..1800 {
+            if let Some((_, action)) = actions.iter().find(|(at, _)| *at == tick) {
+                recorded.handle_scene_action(*action);
+            }
+            if tick == 1000 {
+                recorded.apply_settings(Settings {
+                    ball_speed: 7.0,
+                    ..recorded.settings
+                });
+            }
+            recorded.simulate(1.0 / replay.simulation_rate);
+        }
+        recorded.stop_recording();
+
+        let loaded = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        let mut replayed = Game::new(loaded.seed, loaded.settings, GamePhase::Start);
+        replayed.replay(ReplayPlayer::new(&loaded));
+        loop {
+            replayed.play_replay_events();
+            if replayed.is_replay_finished() {
+                break;
+            }
+            replayed.simulate(1.0 / loaded.simulation_rate);
+        }
+
+        assert_eq!(replayed.tick(), recorded.tick());
+        assert_eq!(replayed.settings, recorded.settings);
+        assert_eq!(replayed.scene.score(), recorded.scene.score());
+        assert_ne!(recorded.scene.score(), Score::default());
+        assert_eq!(replayed.scene.ball.position, recorded.scene.ball.position);
+    }
+}
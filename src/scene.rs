@@ -1,14 +1,38 @@
-use cgmath::{Deg, Matrix4, Point3, Vector2, Vector3, Zero};
+use cgmath::{Deg, InnerSpace, Matrix4, Point3, Vector2, Vector3, VectorSpace, Zero};
 use num::clamp;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
 
 use crate::ball::Ball;
 use crate::camera::Camera;
-use crate::graphics_manager::constants::{WINDOW_HEIGHT, WINDOW_WIDTH};
-use crate::graphics_manager::structures::ModelMesh;
+use crate::collision::{self, Axis, Face};
+use crate::deflection::Deflection;
+use crate::mesh::ModelMesh;
 use crate::paddle::Paddle;
+use crate::rally::RallySpeed;
+use crate::score::{MatchRules, Score, Side};
 use crate::wall::Wall;
 
+/// Upper bound on the bounces resolved in a single update, so a ball squeezed
+/// between two faces can't keep the loop going forever
+const MAX_BOUNCES_PER_UPDATE: usize = 16;
+
+/// Overlap between the ball and a paddle that is still considered touching
+const SQUEEZE_TOLERANCE: f32 = 1e-3;
+
+/// Width over height of the view of the court, until the window it is drawn in says otherwise
+const DEFAULT_ASPECT_RATIO: f32 = 800.0 / 600.0;
+
+/// Seconds the ball waits in the centre of the court before each serve after a point
+const SERVE_DELAY: f32 = 1.0;
+
+/// Horizontal speed of the ball when it is served, unless changed in the settings
+pub const DEFAULT_BALL_SPEED: f32 = 4.0;
+
+/// Speed of the paddles while a player holds a direction, unless changed in the settings
+pub const DEFAULT_PADDLE_SPEED: f32 = 2.0;
+
 pub struct ModelData {
     pub model_mesh: ModelMesh,
     pub model_transform: Matrix4<f32>,
@@ -21,6 +45,57 @@ pub struct Scene {
     pub top_wall: Wall,
     pub bottom_wall: Wall,
     pub ball: Ball,
+    pub deflection: Deflection,
+    pub rally: RallySpeed,
+    pub rules: MatchRules,
+    /// Horizontal speed of the ball when it is served
+    pub ball_speed: f32,
+    pub paddle_speed: f32,
+    court: Court,
+    score: Score,
+    /// Paddle hits since the ball was last served
+    rally_length: u32,
+    /// Speed the ball was last served or hit at
+    rally_speed: f32,
+    pending_serve: Option<PendingServe>,
+    /// Where the moving objects were before the last update
+    previous: Snapshot,
+    seed: u64,
+    /// Source of every random decision in the scene, so a seed replays a whole game
+    rng: ChaCha8Rng,
+}
+
+/// Dimensions of the playing field.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Court {
+    /// Distance from the centre of the court to the middle of each wall
+    pub wall_distance: f32,
+    /// Distance from the centre of the court at which the ball counts as out
+    pub out_of_bounds: f32,
+}
+
+impl Default for Court {
+    fn default() -> Self {
+        Self {
+            wall_distance: 3.2,
+            out_of_bounds: 4.7,
+        }
+    }
+}
+
+/// Positions of the objects that move, to draw them in between two updates
+#[derive(Clone, Copy)]
+struct Snapshot {
+    left_paddle: Vector3<f32>,
+    right_paddle: Vector3<f32>,
+    ball: Vector3<f32>,
+}
+
+/// A serve waiting for its delay to run out
+struct PendingServe {
+    towards: Side,
+    remaining: f32,
 }
 
 mod color {
@@ -29,6 +104,7 @@ mod color {
     pub const BLUE: [f32; 3] = [0.0, 0.0, 1.0];
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Action {
     LeftPaddleUp,
     LeftPaddleDown,
@@ -36,26 +112,60 @@ pub enum Action {
     RightPaddleUp,
     RightPaddleDown,
     RightPaddleStop,
+    /// Moves the left paddle at this percentage of the paddle speed, downwards when positive
+    LeftPaddleSteer(i8),
+    RightPaddleSteer(i8),
     Kickoff,
     GameOver,
     ResetGame,
 }
 
+/// Where a paddle is going.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Stop,
+}
+
+impl Action {
+    /// The action sending the paddle of `side` towards `direction`.
+    pub fn paddle(side: Side, direction: Direction) -> Action {
+        match (side, direction) {
+            (Side::Left, Direction::Up) => Action::LeftPaddleUp,
+            (Side::Left, Direction::Down) => Action::LeftPaddleDown,
+            (Side::Left, Direction::Stop) => Action::LeftPaddleStop,
+            (Side::Right, Direction::Up) => Action::RightPaddleUp,
+            (Side::Right, Direction::Down) => Action::RightPaddleDown,
+            (Side::Right, Direction::Stop) => Action::RightPaddleStop,
+        }
+    }
+
+    /// The action moving the paddle of `side` at `speed` times the paddle speed,
+    /// from -1 upwards to 1 downwards.
+    pub fn steer(side: Side, speed: f32) -> Action {
+        let percent = (speed.clamp(-1.0, 1.0) * 100.0).round() as i8;
+        match side {
+            Side::Left => Action::LeftPaddleSteer(percent),
+            Side::Right => Action::RightPaddleSteer(percent),
+        }
+    }
+}
+
 impl Scene {
-    pub fn new() -> Self {
-        Self {
-            camera: Camera::new(
+    /// Scene whose random decisions all follow from `seed`.
+    pub fn new(seed: u64) -> Self {
+        let mut scene = Self {
+            camera: Camera::perspective(
                 Matrix4::look_at(
                     Point3::new(0.0, 0.0, 10.0),
                     Point3::new(0.0, 0.0, 0.0),
                     Vector3::new(0.0, 1.0, 0.0),
                 ),
-                cgmath::perspective(
-                    Deg(45.0),
-                    WINDOW_WIDTH as f32 / WINDOW_HEIGHT as f32,
-                    0.1,
-                    10.0,
-                ),
+                Deg(45.0),
+                DEFAULT_ASPECT_RATIO,
+                0.1,
+                10.0,
             ),
             left_paddle: Paddle::new(
                 Vector3 {
@@ -80,7 +190,7 @@ impl Scene {
             top_wall: Wall::new(
                 Vector3 {
                     x: 0.0,
-                    y: -3.2,
+                    y: -Court::default().wall_distance,
                     z: 0.0,
                 },
                 0.2,
@@ -89,14 +199,33 @@ impl Scene {
             bottom_wall: Wall::new(
                 Vector3 {
                     x: 0.0,
-                    y: 3.2,
+                    y: Court::default().wall_distance,
                     z: 0.0,
                 },
                 0.2,
                 10.0,
             ),
             ball: Ball::new(Vector3::zero(), 0.2, color::GREEN),
-        }
+            deflection: Deflection::default(),
+            rally: RallySpeed::default(),
+            rules: MatchRules::default(),
+            ball_speed: DEFAULT_BALL_SPEED,
+            paddle_speed: DEFAULT_PADDLE_SPEED,
+            court: Court::default(),
+            score: Score::default(),
+            rally_length: 0,
+            rally_speed: 0.0,
+            pending_serve: None,
+            previous: Snapshot {
+                left_paddle: Vector3::zero(),
+                right_paddle: Vector3::zero(),
+                ball: Vector3::zero(),
+            },
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        };
+        scene.previous = scene.snapshot();
+        scene
     }
 
     pub fn get_model_data(&self) -> Vec<ModelData> {
@@ -124,132 +253,826 @@ impl Scene {
         ]
     }
 
-    pub fn get_model_transforms(&self) -> Vec<Matrix4<f32>> {
+    /// Transforms of the models `alpha` of the way between the state before the
+    /// last update, at `0.0`, and the current one, at `1.0`.
+    pub fn get_model_transforms(&self, alpha: f32) -> Vec<Matrix4<f32>> {
+        let previous = &self.previous;
         vec![
-            Matrix4::from_translation(self.left_paddle.position),
-            Matrix4::from_translation(self.right_paddle.position),
+            Matrix4::from_translation(previous.left_paddle.lerp(self.left_paddle.position, alpha)),
+            Matrix4::from_translation(previous.right_paddle.lerp(self.right_paddle.position, alpha)),
             Matrix4::from_translation(self.top_wall.position),
             Matrix4::from_translation(self.bottom_wall.position),
-            Matrix4::from_translation(self.ball.position),
+            Matrix4::from_translation(previous.ball.lerp(self.ball.position, alpha)),
         ]
     }
 
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            left_paddle: self.left_paddle.position,
+            right_paddle: self.right_paddle.position,
+            ball: self.ball.position,
+        }
+    }
+
     pub fn update(&mut self, delta_time: f32) {
-        // FIXME: this whole thing is buggy.
-        // Sometimes the ball will penetrate a bit in the paddle and get stuck
-        // Might even pierce its way through, given enough time
+        if delta_time <= 0.0 {
+            return;
+        }
+        self.previous = self.snapshot();
 
         // NOTE: positive Y is downwards, so upper_boundary < lower_boundary
 
         // geometry
         let upper_boundary = self.top_wall.position.y + (self.top_wall.height / 2.0);
-        let lower_boundary = self.bottom_wall.position.y - (self.top_wall.height / 2.0);
-        let hpw = self.left_paddle.width / 2.0; // Half paddle width. Both paddles have the same width.
+        let lower_boundary = self.bottom_wall.position.y - (self.bottom_wall.height / 2.0);
         let hph = self.left_paddle.height / 2.0; // Half paddle height. Both paddles have the same height.
-        let lpx = self.left_paddle.position.x; // Left paddle x position
-        let lpy = self.left_paddle.position.y; // Left paddle y position
-        let rpx = self.right_paddle.position.x; // Right paddle x position
-        let rpy = self.right_paddle.position.y; // Right paddle y position
-        let br = self.ball.side_length / 2.0; // Ball "radius"
-        let bpx = self.ball.position.x; // Ball x position
-        let bpy = self.ball.position.y; // Ball y position
+
+        self.left_paddle.accelerate(delta_time);
+        self.right_paddle.accelerate(delta_time);
 
         // CONTACT OF PADDLES AND WALLS
-        // UPDATES PADDLE POSITION
         // Simulates paddles touching the walls by limiting the max and min values for the Y component of the paddle's position
-        self.left_paddle.position.y = clamp(
-            lpy + (delta_time * self.left_paddle.velocity),
+        let left_paddle_free_y =
+            self.left_paddle.position.y + (delta_time * self.left_paddle.velocity);
+        let right_paddle_free_y =
+            self.right_paddle.position.y + (delta_time * self.right_paddle.velocity);
+        let left_paddle_y = clamp(
+            left_paddle_free_y,
             upper_boundary + hph,
             lower_boundary - hph,
         );
-        self.right_paddle.position.y = clamp(
-            self.right_paddle.position.y + (delta_time * self.right_paddle.velocity),
+        let right_paddle_y = clamp(
+            right_paddle_free_y,
             upper_boundary + hph,
             lower_boundary - hph,
         );
 
+        // Paddles are swept along with the ball at the speed they actually move
+        // this frame, which is slower than their velocity when they reach a wall
+        let left_paddle_velocity = (left_paddle_y - self.left_paddle.position.y) / delta_time;
+        let right_paddle_velocity = (right_paddle_y - self.right_paddle.position.y) / delta_time;
+
         // CONTACT OF BALL AND OTHER OBJECTS
-        // UPDATES BALL VELOCITY
-        // Vertical contact - inverts Y component of velocity
-        let is_touching_walls = self.ball.position.y + (self.ball.side_length / 2.0)
-            > lower_boundary
-            || self.ball.position.y - (self.ball.side_length / 2.0) < upper_boundary;
+        let faces = self.collision_faces(left_paddle_velocity, right_paddle_velocity);
+        self.sweep_ball(delta_time, &faces);
 
-        let is_touching_top_of_left_paddle = false; // unimplemented
-        let is_touching_top_of_right_paddle = false; // unimplemented
+        self.left_paddle.position.y = left_paddle_y;
+        self.right_paddle.position.y = right_paddle_y;
+        if left_paddle_y != left_paddle_free_y {
+            self.left_paddle.hit_wall();
+        }
+        if right_paddle_y != right_paddle_free_y {
+            self.right_paddle.hit_wall();
+        }
 
-        let is_touching_bottom_of_left_paddle = false; // unimplemented
-        let is_touching_bottom_of_right_paddle = false; // unimplemented
+        // A paddle end can squeeze the ball against a wall, leaving it nowhere to go vertically
+        squeeze_out(&mut self.ball, &self.left_paddle, 1.0);
+        squeeze_out(&mut self.ball, &self.right_paddle, -1.0);
 
-        let is_touching_bottom_or_top_of_paddles =
-            is_touching_bottom_of_left_paddle ||
-            is_touching_bottom_of_right_paddle ||
-            is_touching_top_of_left_paddle ||
-            is_touching_top_of_right_paddle;
+        // SCORING
+        self.update_serve(delta_time);
+        if self.ball.position.x > self.court.out_of_bounds {
+            self.award_point(Side::Left);
+        } else if self.ball.position.x < -self.court.out_of_bounds {
+            self.award_point(Side::Right);
+        }
+    }
 
-        let is_vertical_contact =
-            is_touching_walls || is_touching_bottom_or_top_of_paddles;
-        if is_vertical_contact {
-            self.ball.velocity.y *= -1.0;
+    /// Counts down the pending serve, if any, and serves the ball once it's due.
+    fn update_serve(&mut self, delta_time: f32) {
+        if let Some(pending_serve) = &mut self.pending_serve {
+            pending_serve.remaining -= delta_time;
+            if pending_serve.remaining <= 0.0 {
+                let towards = pending_serve.towards;
+                self.pending_serve = None;
+                self.serve(towards);
+            }
         }
+    }
 
-        // Horizontal contact - inverts X component of velocity
-        let is_ball_touching_right_face_of_left_paddle =
-            bpx - br < lpx + hpw && // ball touches the right face of the left paddle
-            bpy + br > lpy - hph && // ball is under the top of the left paddle
-            bpy - br < lpy + hph; // ball is over the bottom of the left paddle
-        let is_ball_touching_left_face_of_right_paddle =
-            bpx + br > rpx - hpw && // ball touches the left face of the right paddle
-            bpy + br > rpy - hph && // ball is under the top of the right paddle
-            bpy - br < rpy + hph;   // ball is over the bottom of the right paddle
+    /// Gives `side` a point and, unless that wins the match, brings the ball back to
+    /// the centre to be served towards the player who lost the point.
+    fn award_point(&mut self, side: Side) {
+        self.score.point_to(side);
+        log::debug!(
+            "Point to {:?}, {} - {}",
+            side,
+            self.score.left,
+            self.score.right
+        );
+        self.ball.position.x = 0.0;
+        self.ball.position.y = 0.0;
+        self.ball.velocity = Vector2::zero();
+        // The ball jumps back to the centre, it must not be drawn flying across the court
+        self.previous.ball = self.ball.position;
+
+        self.pending_serve = match self.winner() {
+            None => Some(PendingServe {
+                towards: side.opponent(),
+                remaining: SERVE_DELAY,
+            }),
+            Some(_) => None,
+        };
+    }
 
-        // NOTE: Ignore case where ball touches behind the paddle, per the game mechanics this would be impossible
+    /// Launches the ball from wherever it is towards `side`, at a random vertical angle.
+    ///
+    /// A new rally starts, back at the ball speed of the settings.
+    fn serve(&mut self, towards: Side) {
+        self.rally_length = 0;
+        let direction = match towards {
+            Side::Left => -1.0,
+            Side::Right => 1.0,
+        };
+        self.ball.velocity = Vector2 {
+            x: direction * self.ball_speed,
+            y: self.rng.gen_range(-1.0..1.0),
+        };
+        self.rally_speed = self.ball.velocity.magnitude();
+    }
 
-        let is_horizontal_contact =
-            is_ball_touching_right_face_of_left_paddle || is_ball_touching_left_face_of_right_paddle;
-        if is_horizontal_contact {
-            self.ball.velocity.x *= -1.0;
+    /// Faces the ball can bounce off, taken at the start of the frame.
+    fn collision_faces(&self, left_paddle_velocity: f32, right_paddle_velocity: f32) -> Vec<Face> {
+        let hpw = self.left_paddle.width / 2.0; // Half paddle width. Both paddles have the same width.
+        let hph = self.left_paddle.height / 2.0; // Half paddle height. Both paddles have the same height.
+        let lpx = self.left_paddle.position.x; // Left paddle x position
+        let lpy = self.left_paddle.position.y; // Left paddle y position
+        let rpx = self.right_paddle.position.x; // Right paddle x position
+        let rpy = self.right_paddle.position.y; // Right paddle y position
+
+        // NOTE: Ignore faces behind the paddles, per the game mechanics the ball can't reach them
+        // NOTE: Paddle ends move with the paddle, so a paddle running into the ball pushes it vertically
+        vec![
+            // Right face of the left paddle
+            Face::new(
+                Axis::X,
+                lpx + hpw,
+                1.0,
+                (lpy - hph, lpy + hph),
+                Vector2::new(0.0, left_paddle_velocity),
+            ),
+            // Top of the left paddle
+            Face::new(
+                Axis::Y,
+                lpy - hph,
+                -1.0,
+                (lpx - hpw, lpx + hpw),
+                Vector2::new(0.0, left_paddle_velocity),
+            ),
+            // Bottom of the left paddle
+            Face::new(
+                Axis::Y,
+                lpy + hph,
+                1.0,
+                (lpx - hpw, lpx + hpw),
+                Vector2::new(0.0, left_paddle_velocity),
+            ),
+            // Left face of the right paddle
+            Face::new(
+                Axis::X,
+                rpx - hpw,
+                -1.0,
+                (rpy - hph, rpy + hph),
+                Vector2::new(0.0, right_paddle_velocity),
+            ),
+            // Top of the right paddle
+            Face::new(
+                Axis::Y,
+                rpy - hph,
+                -1.0,
+                (rpx - hpw, rpx + hpw),
+                Vector2::new(0.0, right_paddle_velocity),
+            ),
+            // Bottom of the right paddle
+            Face::new(
+                Axis::Y,
+                rpy + hph,
+                1.0,
+                (rpx - hpw, rpx + hpw),
+                Vector2::new(0.0, right_paddle_velocity),
+            ),
+            // Bottom face of the top wall
+            Face::new(
+                Axis::Y,
+                self.top_wall.position.y + (self.top_wall.height / 2.0),
+                1.0,
+                wall_span(&self.top_wall),
+                Vector2::zero(),
+            ),
+            // Top face of the bottom wall
+            Face::new(
+                Axis::Y,
+                self.bottom_wall.position.y - (self.bottom_wall.height / 2.0),
+                -1.0,
+                wall_span(&self.bottom_wall),
+                Vector2::zero(),
+            ),
+        ]
+    }
+
+    /// Moves the ball through `delta_time`, bouncing off every face it meets on the way.
+    ///
+    /// Each bounce moves the ball to the exact contact point and spends the rest
+    /// of the time step from there, so no step is ever long enough to carry the
+    /// ball through a face.
+    fn sweep_ball(&mut self, delta_time: f32, faces: &[Face]) {
+        let radius = self.ball.side_length / 2.0;
+        let mut position = self.ball.position.truncate();
+        let mut velocity = self.ball.velocity;
+        let mut remaining = delta_time;
+
+        // If the loop runs out of bounces the ball is wedged between faces, so it
+        // stays at the last contact point instead of being carried through one of them
+        for _ in 0..MAX_BOUNCES_PER_UPDATE {
+            // Faces are described at the start of the frame, so move them to the current time
+            let current_faces: Vec<Face> = faces
+                .iter()
+                .map(|face| advance_face(face, delta_time - remaining))
+                .collect();
+
+            match collision::earliest_hit(position, velocity, radius, &current_faces, remaining) {
+                Some((index, time)) => {
+                    let face = &current_faces[index];
+                    position += velocity * time;
+                    velocity = match face.axis {
+                        // Every vertical face in the court is the front of a paddle
+                        Axis::X => {
+                            self.rally_length += 1;
+                            let speed = self
+                                .rally
+                                .after_hit(velocity.magnitude(), self.rally_length);
+                            let velocity = self.deflect(position, speed, face);
+                            self.rally_speed = velocity.magnitude();
+                            velocity
+                        }
+                        Axis::Y => collision::reflect(velocity, face),
+                    };
+                    remaining -= time;
+                }
+                None => {
+                    position += velocity * remaining;
+                    break;
+                }
+            }
         }
 
-        // Update position
-        self.ball.position.x += delta_time * self.ball.velocity.x;
-        self.ball.position.y += delta_time * self.ball.velocity.y;
+        self.ball.position.x = position.x;
+        self.ball.position.y = position.y;
+        self.ball.velocity = velocity;
+    }
+
+    /// Velocity of a ball at `position` leaving the front of a paddle at `speed`.
+    ///
+    /// The further from the centre of the paddle the ball hits, the steeper it
+    /// leaves. A moving paddle then adds some of its own vertical velocity, as
+    /// much as its `english` says, but never beyond the `max_speed` of `rally`.
+    fn deflect(&self, position: Vector2<f32>, speed: f32, front: &Face) -> Vector2<f32> {
+        let radius = self.ball.side_length / 2.0;
+        let centre = (front.span.0 + front.span.1) / 2.0;
+        let reach = (front.span.1 - front.span.0) / 2.0 + radius;
+        let offset = (position.y - centre) / reach;
+
+        // Only the left paddle faces towards positive X
+        let paddle = if front.normal > 0.0 {
+            &self.left_paddle
+        } else {
+            &self.right_paddle
+        };
+        let mut velocity = self.deflection.velocity(offset, speed, front.normal);
+        velocity.y += paddle.motion.english * front.velocity.y;
+        if velocity.magnitude() > self.rally.max_speed {
+            velocity = velocity.normalize_to(self.rally.max_speed);
+        }
+        velocity
     }
 
-    pub fn game_over(&self) -> bool {
-        self.ball.position.x > 4.7 || self.ball.position.x < -4.7
+    pub fn court(&self) -> Court {
+        self.court
+    }
+
+    /// Moves the walls and the out of bounds limits to fit `court`.
+    pub fn set_court(&mut self, court: Court) {
+        self.court = court;
+        self.top_wall.position.y = -court.wall_distance;
+        self.bottom_wall.position.y = court.wall_distance;
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    /// Paddle hits since the ball was last served.
+    pub fn rally_length(&self) -> u32 {
+        self.rally_length
+    }
+
+    /// Speed the ball was last served or hit at, which grows along the rally as
+    /// `rally` says. It outlasts the point until the next serve.
+    pub fn rally_speed(&self) -> f32 {
+        self.rally_speed
+    }
+
+    /// The player who won the match, once the score reaches the target set by `rules`.
+    pub fn winner(&self) -> Option<Side> {
+        self.score.winner(&self.rules)
     }
 
     pub fn handle_action(&mut self, action: Action) {
+        let left_speed = self.left_paddle.top_speed(self.paddle_speed);
+        let right_speed = self.right_paddle.top_speed(self.paddle_speed);
         match action {
-            // positive y is downwards
-            Action::LeftPaddleUp => self.left_paddle.velocity = -2.0,
-            Action::LeftPaddleDown => self.left_paddle.velocity = 2.0,
-            Action::LeftPaddleStop => self.left_paddle.velocity = 0.0,
-            Action::RightPaddleUp => self.right_paddle.velocity = -2.0,
-            Action::RightPaddleDown => self.right_paddle.velocity = 2.0,
-            Action::RightPaddleStop => self.right_paddle.velocity = 0.0,
+            // positive y is downwards, paddles get to these velocities as fast as their motion allows
+            Action::LeftPaddleUp => self.left_paddle.target_velocity = -left_speed,
+            Action::LeftPaddleDown => self.left_paddle.target_velocity = left_speed,
+            Action::LeftPaddleStop => self.left_paddle.target_velocity = 0.0,
+            Action::RightPaddleUp => self.right_paddle.target_velocity = -right_speed,
+            Action::RightPaddleDown => self.right_paddle.target_velocity = right_speed,
+            Action::RightPaddleStop => self.right_paddle.target_velocity = 0.0,
+            Action::LeftPaddleSteer(percent) => {
+                self.left_paddle.target_velocity = left_speed * f32::from(percent) / 100.0
+            }
+            Action::RightPaddleSteer(percent) => {
+                self.right_paddle.target_velocity = right_speed * f32::from(percent) / 100.0
+            }
             Action::Kickoff => {
-                let mut rng = rand::thread_rng();
-                self.ball.velocity = Vector2 {
-                    x: -4.0,
-                    y: rng.gen_range(-1.0..1.0),
-                };
-                if rand::random() {
-                    self.ball.velocity.x *= -1.0;
+                log::debug!("Kickoff");
+                if self.rng.gen() {
+                    self.serve(Side::Left);
+                } else {
+                    self.serve(Side::Right);
                 }
             }
             Action::GameOver => {
                 self.ball.velocity = cgmath::vec2(0.0, 0.0);
-                self.left_paddle.velocity = 0.0;
-                self.right_paddle.velocity = 0.0;
+                self.left_paddle.stop();
+                self.right_paddle.stop();
+                self.pending_serve = None;
             }
             Action::ResetGame => {
                 self.ball.position.x = 0.0;
                 self.ball.position.y = 0.0;
+                self.ball.velocity = cgmath::vec2(0.0, 0.0);
                 self.left_paddle.position.y = 0.0;
+                self.left_paddle.stop();
                 self.right_paddle.position.y = 0.0;
+                self.right_paddle.stop();
+                self.score = Score::default();
+                self.rally_length = 0;
+                self.rally_speed = 0.0;
+                self.pending_serve = None;
+                self.previous = self.snapshot();
             }
         }
     }
 }
+
+/// Horizontal extent of a wall
+fn wall_span(wall: &Wall) -> (f32, f32) {
+    (
+        wall.position.x - (wall.width / 2.0),
+        wall.position.x + (wall.width / 2.0),
+    )
+}
+
+/// Pushes the ball out through the front of `paddle` if the two overlap.
+///
+/// `front` is the direction the paddle faces along X. The sweep keeps the ball out
+/// of paddles everywhere except when a paddle end pins it against a wall, and
+/// sending it back into the court is the only way out of that corner.
+fn squeeze_out(ball: &mut Ball, paddle: &Paddle, front: f32) {
+    let br = ball.side_length / 2.0; // Ball "radius"
+    let hpw = paddle.width / 2.0; // Half paddle width
+    let hph = paddle.height / 2.0; // Half paddle height
+
+    let overlap_x = hpw + br - (ball.position.x - paddle.position.x).abs();
+    let overlap_y = hph + br - (ball.position.y - paddle.position.y).abs();
+    if overlap_x <= SQUEEZE_TOLERANCE || overlap_y <= SQUEEZE_TOLERANCE {
+        return;
+    }
+
+    ball.position.x = paddle.position.x + front * (hpw + br);
+    ball.velocity.x = front * ball.velocity.x.abs();
+}
+
+/// Where a moving face will be after `time`
+fn advance_face(face: &Face, time: f32) -> Face {
+    let (offset, slide) = match face.axis {
+        Axis::X => (face.velocity.x * time, face.velocity.y * time),
+        Axis::Y => (face.velocity.y * time, face.velocity.x * time),
+    };
+
+    Face {
+        position: face.position + offset,
+        span: (face.span.0 + slide, face.span.1 + slide),
+        ..*face
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paddle::PaddleMotion;
+    use crate::rally::Progression;
+    use cgmath::Angle;
+
+    // Closest the ball centre may get to each paddle or wall
+    const LEFT_LIMIT: f32 = -3.8;
+    const RIGHT_LIMIT: f32 = 3.8;
+    const UPPER_LIMIT: f32 = -3.0;
+    const LOWER_LIMIT: f32 = 3.0;
+    const TOLERANCE: f32 = 1e-3;
+
+    fn scene_with_ball(position: Vector2<f32>, velocity: Vector2<f32>) -> Scene {
+        let mut scene = Scene::new(0);
+        scene.ball.position = position.extend(0.0);
+        scene.ball.velocity = velocity;
+        scene
+    }
+
+    fn assert_inside_court(scene: &Scene) {
+        let position = scene.ball.position;
+        assert!(
+            position.x >= LEFT_LIMIT - TOLERANCE && position.x <= RIGHT_LIMIT + TOLERANCE,
+            "ball went through a paddle: {:?}",
+            position
+        );
+        assert!(
+            position.y >= UPPER_LIMIT - TOLERANCE && position.y <= LOWER_LIMIT + TOLERANCE,
+            "ball went through a wall: {:?}",
+            position
+        );
+    }
+
+    #[test]
+    fn ball_bounces_off_left_paddle() {
+        let mut scene = scene_with_ball(Vector2::new(-3.0, 0.0), Vector2::new(-4.0, 0.0));
+        scene.update(0.5);
+
+        assert!(scene.ball.velocity.x > 0.0);
+        assert!((scene.ball.position.x - (LEFT_LIMIT + 1.2)).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn ball_bounces_off_right_paddle() {
+        let mut scene = scene_with_ball(Vector2::new(3.0, 0.0), Vector2::new(4.0, 0.0));
+        scene.update(0.5);
+
+        assert!(scene.ball.velocity.x < 0.0);
+        assert!((scene.ball.position.x - (RIGHT_LIMIT - 1.2)).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn paddle_deflection_depends_on_contact_offset() {
+        let mut scene = scene_with_ball(Vector2::new(3.0, 0.55), Vector2::new(4.0, 0.0));
+        scene.update(0.5);
+
+        let angle = Deg::atan2(scene.ball.velocity.y, -scene.ball.velocity.x);
+        assert!((angle.0 - 30.0).abs() < 0.1);
+        assert!((scene.ball.velocity.magnitude() - 4.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn paddle_deflection_is_clamped_to_max_angle() {
+        let mut scene = scene_with_ball(Vector2::new(-3.0, -1.1), Vector2::new(-4.0, 0.0));
+        scene.deflection.max_angle = Deg(45.0);
+        scene.update(0.5);
+
+        let angle = Deg::atan2(scene.ball.velocity.y, scene.ball.velocity.x);
+        assert!((angle.0 + 45.0).abs() < 0.1);
+        assert!((scene.ball.velocity.magnitude() - 4.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn fast_ball_never_tunnels_through_paddles() {
+        for &speed in &[10.0, 100.0, 1_000.0, 10_000.0] {
+            for &delta_time in &[1.0 / 144.0, 1.0 / 60.0, 0.25, 1.0] {
+                for &y in &[-0.9, 0.0, 0.5] {
+                    // Without deflection the ball keeps bouncing between both paddles
+                    let mut scene =
+                        scene_with_ball(Vector2::new(0.0, y), Vector2::new(-speed, 0.0));
+                    scene.deflection.max_angle = Deg(0.0);
+                    scene.update(delta_time);
+                    assert_inside_court(&scene);
+
+                    let mut scene =
+                        scene_with_ball(Vector2::new(0.0, y), Vector2::new(speed, 0.0));
+                    scene.deflection.max_angle = Deg(0.0);
+                    scene.update(delta_time);
+                    assert_inside_court(&scene);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fast_ball_never_tunnels_through_walls() {
+        for &speed in &[10.0, 100.0, 1_000.0, 10_000.0] {
+            for &delta_time in &[1.0 / 60.0, 0.25, 1.0] {
+                let mut scene = scene_with_ball(Vector2::new(0.0, 0.0), Vector2::new(0.0, speed));
+                scene.update(delta_time);
+                assert_inside_court(&scene);
+
+                let mut scene = scene_with_ball(Vector2::new(0.0, 0.0), Vector2::new(0.0, -speed));
+                scene.update(delta_time);
+                assert_inside_court(&scene);
+            }
+        }
+    }
+
+    #[test]
+    fn ball_touching_paddle_and_moving_away_does_not_stick() {
+        let mut scene = scene_with_ball(Vector2::new(LEFT_LIMIT, 0.0), Vector2::new(4.0, 0.0));
+        scene.update(0.1);
+
+        assert!(scene.ball.velocity.x > 0.0);
+        assert!((scene.ball.position.x - (LEFT_LIMIT + 0.4)).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn ball_is_caught_by_paddle_moving_into_its_path() {
+        // The ball passes below the paddle's initial reach, but the paddle moves down in time
+        let mut scene = scene_with_ball(Vector2::new(-3.0, 1.5), Vector2::new(-2.0, 0.0));
+        scene.handle_action(Action::LeftPaddleDown);
+        scene.update(0.5);
+
+        assert!(scene.ball.velocity.x > 0.0);
+        assert!(scene.ball.position.x >= LEFT_LIMIT - TOLERANCE);
+    }
+
+    #[test]
+    fn ball_that_misses_paddles_leaves_the_court() {
+        let mut scene = scene_with_ball(Vector2::new(-3.0, 2.5), Vector2::new(-4.0, 0.0));
+        scene.update(0.3);
+
+        assert!(scene.ball.velocity.x < 0.0);
+        assert!(scene.ball.position.x < LEFT_LIMIT);
+    }
+
+    #[test]
+    fn ball_bounces_off_top_of_left_paddle() {
+        let mut scene = scene_with_ball(Vector2::new(-4.0, -2.0), Vector2::new(0.0, 4.0));
+        scene.update(0.3);
+
+        assert!(scene.ball.velocity.y < 0.0);
+        assert!((scene.ball.position.y - (-1.4)).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn ball_bounces_off_bottom_of_left_paddle() {
+        let mut scene = scene_with_ball(Vector2::new(-4.0, 2.0), Vector2::new(0.0, -4.0));
+        scene.update(0.3);
+
+        assert!(scene.ball.velocity.y > 0.0);
+        assert!((scene.ball.position.y - 1.4).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn ball_bounces_off_top_of_right_paddle() {
+        let mut scene = scene_with_ball(Vector2::new(4.05, -2.0), Vector2::new(0.0, 4.0));
+        scene.update(0.3);
+
+        assert!(scene.ball.velocity.y < 0.0);
+        assert!((scene.ball.position.y - (-1.4)).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn ball_bounces_off_bottom_of_right_paddle() {
+        let mut scene = scene_with_ball(Vector2::new(3.95, 2.0), Vector2::new(0.0, -4.0));
+        scene.update(0.3);
+
+        assert!(scene.ball.velocity.y > 0.0);
+        assert!((scene.ball.position.y - 1.4).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn ball_hitting_paddle_end_at_an_angle_is_not_sent_back_horizontally() {
+        let mut scene = scene_with_ball(Vector2::new(-3.6, -2.0), Vector2::new(-1.0, 4.0));
+        scene.update(0.3);
+
+        assert!(scene.ball.velocity.x < 0.0);
+        assert!(scene.ball.velocity.y < 0.0);
+    }
+
+    #[test]
+    fn moving_paddle_pushes_ball_vertically() {
+        let mut scene = scene_with_ball(Vector2::new(-4.0, -1.5), Vector2::new(0.0, 0.0));
+        scene.handle_action(Action::LeftPaddleUp);
+        scene.update(0.3);
+
+        assert!(scene.ball.velocity.y < -2.0);
+        let paddle_top = scene.left_paddle.position.y - scene.left_paddle.height / 2.0;
+        assert!(scene.ball.position.y + scene.ball.side_length / 2.0 <= paddle_top + TOLERANCE);
+    }
+
+    #[test]
+    fn paddle_with_inertia_picks_up_speed_and_bounces_off_walls() {
+        let mut scene = Scene::new(0);
+        scene.left_paddle.motion = PaddleMotion {
+            acceleration: 4.0,
+            max_speed: Some(3.0),
+            bounce: 0.5,
+            ..PaddleMotion::default()
+        };
+        scene.handle_action(Action::LeftPaddleUp);
+        scene.update(0.25);
+        assert_eq!(scene.left_paddle.velocity, -1.0);
+        assert_eq!(scene.left_paddle.position.y, -0.25);
+
+        // Running into the top wall sends it back down at half the speed
+        scene.left_paddle.position.y = -2.0;
+        scene.update(0.25);
+        assert!((scene.left_paddle.position.y - (UPPER_LIMIT + 0.9)).abs() < TOLERANCE);
+        assert_eq!(scene.left_paddle.velocity, 1.0);
+        assert_eq!(scene.right_paddle.velocity, 0.0);
+    }
+
+    #[test]
+    fn moving_paddle_gives_the_ball_english() {
+        let hit = |english: f32, max_speed: f32| {
+            let mut scene = scene_with_ball(Vector2::new(3.5, 0.0), Vector2::new(4.0, 0.0));
+            scene.right_paddle.motion.english = english;
+            scene.rally.max_speed = max_speed;
+            scene.handle_action(Action::RightPaddleDown);
+            scene.update(0.1);
+            scene.ball.velocity
+        };
+
+        assert!(hit(0.0, 12.0).y.abs() < 0.5);
+        // Half the velocity of the paddle comes on top of the deflection
+        assert!((hit(0.5, 12.0).y - hit(0.0, 12.0).y - 1.0).abs() < TOLERANCE);
+        assert!(hit(0.5, 12.0).x < 0.0);
+        // Still no faster than a ball may leave a paddle
+        assert!(hit(1.0, 4.0).magnitude() <= 4.0 + TOLERANCE);
+        assert!(hit(1.0, 4.0).y > hit(0.0, 4.0).y);
+    }
+
+    #[test]
+    fn rally_speeds_up_with_each_hit_until_the_next_serve() {
+        let mut scene = scene_with_ball(Vector2::new(0.0, 0.0), Vector2::new(4.0, 0.0));
+        scene.rally.progression = Progression::Linear;
+        scene.rally.increment = 0.5;
+        // Both paddle fronts are 7.6 apart, so the ball crosses the court in under two seconds
+        for _ in 0..240 {
+            scene.update(1.0 / 120.0);
+        }
+        assert_eq!(scene.rally_length(), 1);
+        assert!((scene.rally_speed() - 4.5).abs() < TOLERANCE);
+        for _ in 0..240 {
+            scene.update(1.0 / 120.0);
+        }
+        assert_eq!(scene.rally_length(), 2);
+        assert!((scene.rally_speed() - 5.0).abs() < TOLERANCE);
+
+        scene.serve(Side::Left);
+        assert_eq!(scene.rally_length(), 0);
+        assert_eq!(scene.ball.velocity.x, -DEFAULT_BALL_SPEED);
+    }
+
+    #[test]
+    fn rally_outlasts_the_point_until_the_next_serve() {
+        let mut scene = scene_with_ball(Vector2::new(3.0, 0.0), Vector2::new(4.0, 0.0));
+        scene.rally.progression = Progression::Linear;
+        scene.rally.increment = 0.5;
+        scene.update(0.25);
+        assert_eq!(scene.rally_length(), 1);
+
+        // The left paddle is out of the way, so the ball goes out for the right player
+        scene.left_paddle.position.y = 2.5;
+        scene.update(2.0);
+        assert_eq!(scene.score(), Score { left: 0, right: 1 });
+        // While the serve is pending, the rally that ended is still there to show
+        assert_eq!(scene.ball.velocity, Vector2::zero());
+        assert_eq!(scene.rally_length(), 1);
+        assert!((scene.rally_speed() - 4.5).abs() < TOLERANCE);
+
+        scene.update(SERVE_DELAY);
+        assert_eq!(scene.rally_length(), 0);
+        assert_eq!(scene.rally_speed(), scene.ball.velocity.magnitude());
+    }
+
+    #[test]
+    fn ball_pinned_between_paddle_and_wall_is_squeezed_into_the_court() {
+        let mut scene = scene_with_ball(Vector2::new(4.0, -2.75), Vector2::new(0.0, 0.0));
+        scene.right_paddle.position.y = -1.5;
+        scene.handle_action(Action::RightPaddleUp);
+        for _ in 0..60 {
+            scene.update(1.0 / 60.0);
+        }
+
+        assert!((scene.ball.position.x - RIGHT_LIMIT).abs() < TOLERANCE);
+        assert_inside_court(&scene);
+    }
+
+    #[test]
+    fn ball_leaving_the_court_scores_for_the_other_player() {
+        let mut scene = scene_with_ball(Vector2::new(3.0, 2.5), Vector2::new(4.0, 0.0));
+        scene.update(0.5);
+
+        assert_eq!(scene.score(), Score { left: 1, right: 0 });
+        assert_eq!(scene.ball.position, Vector3::zero());
+        assert_eq!(scene.ball.velocity, Vector2::zero());
+    }
+
+    #[test]
+    fn court_sets_walls_and_out_of_bounds() {
+        let mut scene = scene_with_ball(Vector2::new(0.0, 0.0), Vector2::new(0.0, 4.0));
+        scene.set_court(Court {
+            wall_distance: 2.0,
+            out_of_bounds: 6.0,
+        });
+        scene.update(1.0);
+        // Bounced off the bottom wall, which moved closer
+        assert!(scene.ball.position.y < 1.9);
+        assert!(scene.ball.velocity.y < 0.0);
+
+        // Further than the usual limit, but still inside this court
+        scene.ball.position = Vector3::new(5.0, 0.0, 0.0);
+        scene.ball.velocity = Vector2::new(1.0, 0.0);
+        scene.update(0.1);
+        assert_eq!(scene.score(), Score::default());
+    }
+
+    #[test]
+    fn ball_is_served_to_the_player_who_lost_the_point() {
+        let mut scene = scene_with_ball(Vector2::new(-3.0, 2.5), Vector2::new(-4.0, 0.0));
+        scene.update(0.5);
+        assert_eq!(scene.score(), Score { left: 0, right: 1 });
+
+        scene.update(SERVE_DELAY / 2.0);
+        assert_eq!(scene.ball.velocity, Vector2::zero());
+
+        scene.update(SERVE_DELAY / 2.0);
+        assert!(scene.ball.velocity.x < 0.0);
+    }
+
+    #[test]
+    fn match_ends_when_target_score_is_reached() {
+        let mut scene = Scene::new(0);
+        scene.rules = MatchRules {
+            target_score: 2,
+            win_by_two: false,
+        };
+
+        for expected_right_score in 1..=2 {
+            scene.ball.position = Vector3::new(-3.0, 2.5, 0.0);
+            scene.ball.velocity = Vector2::new(-4.0, 0.0);
+            scene.update(0.5);
+            assert_eq!(scene.score().right, expected_right_score);
+        }
+
+        assert_eq!(scene.winner(), Some(Side::Right));
+        // No more serves once the match is over
+        scene.update(SERVE_DELAY * 2.0);
+        assert_eq!(scene.ball.velocity, Vector2::zero());
+    }
+
+    #[test]
+    fn reset_game_clears_the_score() {
+        let mut scene = scene_with_ball(Vector2::new(3.0, 2.5), Vector2::new(4.0, 0.0));
+        scene.update(0.5);
+        scene.handle_action(Action::ResetGame);
+
+        assert_eq!(scene.score(), Score::default());
+        scene.update(SERVE_DELAY * 2.0);
+        assert_eq!(scene.ball.velocity, Vector2::zero());
+    }
+
+    #[test]
+    fn transforms_interpolate_between_updates() {
+        let mut scene = scene_with_ball(Vector2::new(0.0, 0.0), Vector2::new(2.0, 0.0));
+        scene.update(0.5);
+
+        let ball_x = |transforms: &[Matrix4<f32>]| transforms[4].w.x;
+        assert_eq!(ball_x(&scene.get_model_transforms(0.0)), 0.0);
+        assert_eq!(ball_x(&scene.get_model_transforms(0.5)), 0.5);
+        assert_eq!(ball_x(&scene.get_model_transforms(1.0)), 1.0);
+    }
+
+    #[test]
+    fn ball_back_in_the_centre_is_not_interpolated() {
+        let mut scene = scene_with_ball(Vector2::new(4.5, 0.0), Vector2::new(4.0, 0.0));
+        scene.update(0.1);
+
+        assert_eq!(scene.score().left, 1);
+        assert_eq!(scene.get_model_transforms(0.0)[4].w.x, 0.0);
+    }
+
+    #[test]
+    fn same_seed_plays_the_same_game() {
+        let play = |seed: u64| {
+            let mut scene = Scene::new(seed);
+            scene.handle_action(Action::Kickoff);
+            for _ in 0..1000 {
+                scene.update(1.0 / 120.0);
+            }
+            (scene.ball.position, scene.ball.velocity, scene.score())
+        };
+
+        assert_eq!(play(7), play(7));
+        assert_ne!(play(7), play(8));
+    }
+}
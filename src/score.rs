@@ -0,0 +1,107 @@
+/// One of the two players, named after the side of the court they defend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    pub fn opponent(self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+/// What it takes to win a match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchRules {
+    /// Points needed to win the match
+    pub target_score: u32,
+    /// When set, the winner also needs a lead of at least two points
+    pub win_by_two: bool,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            target_score: 11,
+            win_by_two: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Score {
+    pub left: u32,
+    pub right: u32,
+}
+
+impl Score {
+    pub fn of(&self, side: Side) -> u32 {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+        }
+    }
+
+    pub fn point_to(&mut self, side: Side) {
+        match side {
+            Side::Left => self.left += 1,
+            Side::Right => self.right += 1,
+        }
+    }
+
+    /// The player who won the match under `rules`, if any.
+    pub fn winner(&self, rules: &MatchRules) -> Option<Side> {
+        let (leader, lead) = if self.left >= self.right {
+            (Side::Left, self.left - self.right)
+        } else {
+            (Side::Right, self.right - self.left)
+        };
+        let required_lead = if rules.win_by_two { 2 } else { 1 };
+
+        if self.of(leader) >= rules.target_score && lead >= required_lead {
+            Some(leader)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_is_won_at_target_score() {
+        let rules = MatchRules {
+            target_score: 3,
+            win_by_two: false,
+        };
+        let mut score = Score::default();
+        score.point_to(Side::Right);
+        score.point_to(Side::Right);
+        assert_eq!(score.winner(&rules), None);
+
+        score.point_to(Side::Right);
+        assert_eq!(score.winner(&rules), Some(Side::Right));
+    }
+
+    #[test]
+    fn win_by_two_extends_the_match() {
+        let rules = MatchRules {
+            target_score: 3,
+            win_by_two: true,
+        };
+        let score = Score { left: 3, right: 2 };
+        assert_eq!(score.winner(&rules), None);
+
+        let score = Score { left: 4, right: 5 };
+        assert_eq!(score.winner(&rules), None);
+
+        let score = Score { left: 4, right: 6 };
+        assert_eq!(score.winner(&rules), Some(Side::Right));
+    }
+}
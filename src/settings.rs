@@ -0,0 +1,248 @@
+use num::clamp;
+
+use crate::ai::{CpuPlayer, Difficulty};
+use crate::scene::{Scene, DEFAULT_BALL_SPEED, DEFAULT_PADDLE_SPEED};
+use crate::score::{MatchRules, Side};
+
+pub(crate) const BALL_SPEED_RANGE: (f32, f32) = (2.0, 10.0);
+const BALL_SPEED_STEP: f32 = 0.5;
+pub(crate) const PADDLE_SPEED_RANGE: (f32, f32) = (1.0, 6.0);
+const PADDLE_SPEED_STEP: f32 = 0.5;
+pub(crate) const TARGET_SCORE_RANGE: (u32, u32) = (1, 21);
+
+/// How a match is decided.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameMode {
+    /// First player to reach the target score wins
+    Classic,
+    /// The winner also needs a lead of two points
+    WinByTwo,
+}
+
+impl GameMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Classic => "Classic",
+            GameMode::WinByTwo => "Win by two",
+        }
+    }
+
+    /// Name of the mode in files and on the command line.
+    pub fn id(self) -> &'static str {
+        match self {
+            GameMode::Classic => "classic",
+            GameMode::WinByTwo => "win-by-two",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<GameMode> {
+        [GameMode::Classic, GameMode::WinByTwo]
+            .iter()
+            .copied()
+            .find(|mode| mode.id() == id)
+    }
+
+    fn next(self) -> GameMode {
+        match self {
+            GameMode::Classic => GameMode::WinByTwo,
+            GameMode::WinByTwo => GameMode::Classic,
+        }
+    }
+}
+
+/// Who plays the right paddle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Opponent {
+    Human,
+    Cpu(Difficulty),
+}
+
+impl Opponent {
+    pub fn name(self) -> String {
+        match self {
+            Opponent::Human => "Human".to_string(),
+            Opponent::Cpu(difficulty) => format!("CPU {}", difficulty.name()),
+        }
+    }
+
+    /// Name of the opponent in files and on the command line.
+    pub fn id(self) -> &'static str {
+        match self {
+            Opponent::Human => "human",
+            Opponent::Cpu(Difficulty::Easy) => "cpu-easy",
+            Opponent::Cpu(Difficulty::Normal) => "cpu-normal",
+            Opponent::Cpu(Difficulty::Hard) => "cpu-hard",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Opponent> {
+        Opponent::ALL
+            .iter()
+            .copied()
+            .find(|opponent| opponent.id() == id)
+    }
+
+    /// Opponents in the order the settings screen goes through them.
+    pub const ALL: [Opponent; 4] = [
+        Opponent::Human,
+        Opponent::Cpu(Difficulty::Easy),
+        Opponent::Cpu(Difficulty::Normal),
+        Opponent::Cpu(Difficulty::Hard),
+    ];
+}
+
+/// Gameplay options players can change from the settings screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    pub ball_speed: f32,
+    pub paddle_speed: f32,
+    pub target_score: u32,
+    pub game_mode: GameMode,
+    pub opponent: Opponent,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        let rules = MatchRules::default();
+        Self {
+            ball_speed: DEFAULT_BALL_SPEED,
+            paddle_speed: DEFAULT_PADDLE_SPEED,
+            target_score: rules.target_score,
+            game_mode: GameMode::Classic,
+            opponent: Opponent::Human,
+        }
+    }
+}
+
+/// One of the values of `Settings`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting {
+    BallSpeed,
+    PaddleSpeed,
+    TargetScore,
+    GameMode,
+    Opponent,
+}
+
+impl Settings {
+    pub fn match_rules(&self) -> MatchRules {
+        MatchRules {
+            target_score: self.target_score,
+            win_by_two: self.game_mode == GameMode::WinByTwo,
+        }
+    }
+
+    /// Moves `setting` one step up when `steps` is positive or down when it is
+    /// negative, staying within its range.
+    pub fn adjust(&mut self, setting: Setting, steps: i32) {
+        match setting {
+            Setting::BallSpeed => {
+                self.ball_speed = clamp(
+                    self.ball_speed + steps as f32 * BALL_SPEED_STEP,
+                    BALL_SPEED_RANGE.0,
+                    BALL_SPEED_RANGE.1,
+                )
+            }
+            Setting::PaddleSpeed => {
+                self.paddle_speed = clamp(
+                    self.paddle_speed + steps as f32 * PADDLE_SPEED_STEP,
+                    PADDLE_SPEED_RANGE.0,
+                    PADDLE_SPEED_RANGE.1,
+                )
+            }
+            Setting::TargetScore => {
+                self.target_score = clamp(
+                    self.target_score as i32 + steps,
+                    TARGET_SCORE_RANGE.0 as i32,
+                    TARGET_SCORE_RANGE.1 as i32,
+                ) as u32
+            }
+            // Only two modes, so any step toggles between them
+            Setting::GameMode => {
+                if steps % 2 != 0 {
+                    self.game_mode = self.game_mode.next();
+                }
+            }
+            Setting::Opponent => {
+                let count = Opponent::ALL.len() as i32;
+                let current = Opponent::ALL
+                    .iter()
+                    .position(|opponent| *opponent == self.opponent)
+                    .unwrap_or(0) as i32;
+                self.opponent = Opponent::ALL[(current + steps).rem_euclid(count) as usize];
+            }
+        }
+    }
+
+    /// Current value of `setting`, as shown to players.
+    pub fn display(&self, setting: Setting) -> String {
+        match setting {
+            Setting::BallSpeed => format!("{:.1}", self.ball_speed),
+            Setting::PaddleSpeed => format!("{:.1}", self.paddle_speed),
+            Setting::TargetScore => self.target_score.to_string(),
+            Setting::GameMode => self.game_mode.name().to_string(),
+            Setting::Opponent => self.opponent.name(),
+        }
+    }
+
+    pub fn apply(&self, scene: &mut Scene) {
+        scene.ball_speed = self.ball_speed;
+        scene.paddle_speed = self.paddle_speed;
+        scene.rules = self.match_rules();
+    }
+
+    /// The computer player for the right paddle, unless a human plays it.
+    pub fn cpu_player(&self, seed: u64) -> Option<CpuPlayer> {
+        match self.opponent {
+            Opponent::Human => None,
+            Opponent::Cpu(difficulty) => Some(CpuPlayer::new(Side::Right, difficulty, seed)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjusting_stays_within_range() {
+        let mut settings = Settings::default();
+        settings.adjust(Setting::TargetScore, -100);
+        assert_eq!(settings.target_score, TARGET_SCORE_RANGE.0);
+
+        settings.adjust(Setting::BallSpeed, 100);
+        assert_eq!(settings.ball_speed, BALL_SPEED_RANGE.1);
+
+        settings.adjust(Setting::PaddleSpeed, -1);
+        assert_eq!(
+            settings.paddle_speed,
+            DEFAULT_PADDLE_SPEED - PADDLE_SPEED_STEP
+        );
+    }
+
+    #[test]
+    fn game_mode_sets_match_rules() {
+        let mut settings = Settings::default();
+        assert!(!settings.match_rules().win_by_two);
+
+        settings.adjust(Setting::GameMode, 1);
+        assert_eq!(settings.game_mode, GameMode::WinByTwo);
+        assert!(settings.match_rules().win_by_two);
+
+        settings.adjust(Setting::GameMode, -1);
+        assert_eq!(settings.game_mode, GameMode::Classic);
+    }
+
+    #[test]
+    fn opponents_cycle_through_difficulties() {
+        let mut settings = Settings::default();
+        assert!(settings.cpu_player(0).is_none());
+
+        settings.adjust(Setting::Opponent, -1);
+        assert_eq!(settings.opponent, Opponent::Cpu(Difficulty::Hard));
+        assert!(settings.cpu_player(0).is_some());
+
+        settings.adjust(Setting::Opponent, 2);
+        assert_eq!(settings.opponent, Opponent::Cpu(Difficulty::Easy));
+    }
+}
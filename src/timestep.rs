@@ -0,0 +1,102 @@
+/// Simulation updates per second, whatever the frame rate
+pub const DEFAULT_SIMULATION_RATE: f32 = 120.0;
+
+/// Most simulation steps run for a single frame. When a machine can't keep up,
+/// the game slows down instead of spending ever longer frames catching up.
+const MAX_STEPS_PER_FRAME: u32 = 8;
+
+/// Splits the time between frames into simulation steps of a fixed duration.
+///
+/// The time left over after the last whole step is carried to the next frame,
+/// and tells how far rendering is between the last two simulated states.
+pub struct FixedTimestep {
+    step: f32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    /// Timestep running `rate` simulation steps per second.
+    pub fn new(rate: f32) -> Self {
+        Self {
+            step: 1.0 / rate,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Duration of a simulation step, in seconds.
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    /// Adds `frame_time` seconds and returns how many steps should be simulated now.
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        self.accumulator += frame_time.max(0.0);
+
+        let steps = (self.accumulator / self.step).floor() as u32;
+        if steps > MAX_STEPS_PER_FRAME {
+            // Drop the time that can't be caught up with, keeping the fraction of a step
+            self.accumulator %= self.step;
+            return MAX_STEPS_PER_FRAME;
+        }
+
+        self.accumulator -= steps as f32 * self.step;
+        steps
+    }
+
+    /// How far, from `0.0` to `1.0`, the current frame is between the last
+    /// simulated state and the next one.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.step).min(1.0)
+    }
+
+    /// Forgets the time carried over from previous frames.
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_steps_are_taken_and_the_rest_is_kept() {
+        let mut timestep = FixedTimestep::new(100.0);
+        assert_eq!(timestep.advance(0.025), 2);
+        assert!((timestep.alpha() - 0.5).abs() < 1e-3);
+
+        assert_eq!(timestep.advance(0.005), 1);
+        assert!(timestep.alpha() < 1e-3);
+    }
+
+    #[test]
+    fn frame_rate_does_not_change_the_step_count() {
+        let mut slow = FixedTimestep::new(120.0);
+        let mut fast = FixedTimestep::new(120.0);
+
+        let slow_steps: u32 = (0..30).map(|_| slow.advance(1.0 / 30.0)).sum();
+        let fast_steps: u32 = (0..240).map(|_| fast.advance(1.0 / 240.0)).sum();
+        // One step may still be waiting in the accumulator because of rounding
+        assert!((slow_steps as i32 - fast_steps as i32).abs() <= 1);
+        assert!((119..=120).contains(&slow_steps));
+    }
+
+    #[test]
+    fn long_frames_are_capped() {
+        let mut timestep = FixedTimestep::new(120.0);
+        assert_eq!(timestep.advance(5.0), MAX_STEPS_PER_FRAME);
+        // The dropped time doesn't come back on the next frame
+        assert_eq!(timestep.advance(0.0), 0);
+    }
+
+    #[test]
+    fn reset_drops_the_time_carried_over() {
+        let mut timestep = FixedTimestep::new(100.0);
+        assert_eq!(timestep.advance(0.009), 0);
+        timestep.reset();
+
+        assert_eq!(timestep.alpha(), 0.0);
+        // Without the reset, the carried time would have completed a step
+        assert_eq!(timestep.advance(0.002), 0);
+    }
+}
@@ -1,6 +1,6 @@
 use cgmath::Vector3;
 
-use crate::graphics_manager::structures::{ModelMesh, Vertex};
+use crate::mesh::{ModelMesh, Vertex};
 
 const INDICES_DATA: [u32; 6] = [0, 1, 2, 2, 3, 0];
 
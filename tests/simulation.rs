@@ -0,0 +1,33 @@
+use pong_rust::ai::{CpuPlayer, Difficulty};
+use pong_rust::game::{Game, GamePhase};
+use pong_rust::headless;
+use pong_rust::scene::{Action, Scene};
+use pong_rust::score::Side;
+use pong_rust::settings::Settings;
+
+#[test]
+fn scene_runs_without_a_renderer() {
+    let mut scene = Scene::new(1);
+    scene.handle_action(Action::Kickoff);
+    scene.handle_action(Action::LeftPaddleUp);
+    for _ in 0..120 {
+        scene.update(1.0 / 120.0);
+    }
+
+    assert!(scene.left_paddle.position.y < 0.0);
+    assert!(scene.ball.position.x != 0.0);
+}
+
+#[test]
+fn computer_player_wins_against_an_empty_side() {
+    let settings = Settings {
+        target_score: 2,
+        ..Settings::default()
+    };
+    let mut game = Game::new(8, settings, GamePhase::Start);
+    game.add_cpu_player(CpuPlayer::new(Side::Right, Difficulty::Hard, 8));
+
+    let summary = headless::run(&mut game, 120 * 60, 1.0 / 120.0);
+    assert!(summary.matches.right >= 1);
+    assert_eq!(summary.matches.left, 0);
+}